@@ -1,5 +1,6 @@
 use std::sync::Mutex;
 
+use crate::domain::deviation::Deviation;
 use crate::domain::position::Position;
 use crate::domain::starter::{Starter, StarterResult};
 use hypertext::{html_elements, GlobalAttributes};
@@ -109,21 +110,20 @@ impl AlertManager {
     }
     pub fn set(&self, r#type: AlertType, position: &Position, value: bool) -> bool {
         let visible = if let Ok(mut list) = self.0.lock() {
-            let item = list.iter_mut().find(|item| item.r#type == r#type);
-            if let Some(item) = item {
-                item.set(position, value)
-            } else {
-                let mut item = Alert::new(r#type);
-                let empty = item.set(position, value);
-                list.push(item);
-                empty
-            }
+            set_alert(&mut list, r#type, position, value)
         } else {
             false
         };
         self.filter();
         visible
     }
+    /// Shows the status the judge at the position gave the starter
+    pub fn set_status(&self, position: &Position, status: &StarterResult) {
+        if let Ok(mut list) = self.0.lock() {
+            set_status_alert(&mut list, position, status);
+        }
+        self.filter();
+    }
     pub fn filter(&self) {
         let mut list = self.0.lock().expect("Must be able to get list");
         *list = list.drain(..).filter(|x| !x.is_empty()).collect::<Vec<_>>();
@@ -136,6 +136,11 @@ impl AlertManager {
             </ul>
         }
     }
+    pub fn clear(&self) {
+        if let Ok(mut list) = self.0.lock() {
+            list.clear();
+        }
+    }
     pub fn get_length(&self) -> usize {
         self.0.lock().map_or(0, |x| x.len())
     }
@@ -147,6 +152,72 @@ impl AlertManager {
         let mut list = self.0.lock().expect("Must be able to get list");
         *list = starter.warnings.clone();
     }
+
+    /// Replaces the deviation alerts with those of the latest comparison of
+    /// the panel, checking off the position of every judge who deviates.
+    pub fn merge_deviations(&self, deviations: &[Deviation]) {
+        if let Ok(mut list) = self.0.lock() {
+            list.retain(|x| !x.r#type.is_deviation());
+            for deviation in deviations {
+                let (r#type, position) = match deviation {
+                    Deviation::Movement {
+                        position, movement, ..
+                    } => (AlertType::MovementDeviation(*movement), position),
+                    Deviation::Score { position, .. } => (AlertType::ScoreDeviation, position),
+                };
+                match list.iter_mut().find(|item| item.r#type == r#type) {
+                    Some(item) => {
+                        item.set(position, true);
+                    }
+                    None => {
+                        let mut item = Alert::new(r#type);
+                        item.set(position, true);
+                        list.push(item);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sets the alert for the position in a list of alerts, adding the alert
+/// when it is not yet in the list
+pub fn set_alert(
+    list: &mut Vec<Alert>,
+    r#type: AlertType,
+    position: &Position,
+    value: bool,
+) -> bool {
+    match list.iter_mut().find(|item| item.r#type == r#type) {
+        Some(item) => item.set(position, value),
+        None => {
+            let mut item = Alert::new(r#type);
+            let empty = item.set(position, value);
+            list.push(item);
+            empty
+        }
+    }
+}
+
+/// Replaces the status alert of the position. Only statuses which end the
+/// test are alerts, so going back to in progress just clears it.
+pub fn set_status_alert(list: &mut Vec<Alert>, position: &Position, status: &StarterResult) {
+    for item in list.iter_mut() {
+        if let AlertType::Status(_) = item.r#type {
+            item.set(position, false);
+        }
+    }
+    if matches!(
+        status,
+        StarterResult::Eliminated(_)
+            | StarterResult::Withdrawn
+            | StarterResult::NoShow
+            | StarterResult::Retired
+            | StarterResult::Disqualified
+    ) {
+        set_alert(list, AlertType::Status(status.clone()), position, true);
+    }
+    list.retain(|x| !x.is_empty());
 }
 
 #[derive(serde::Serialize, serde::Deserialize, PartialEq, Default, Clone, Debug)]
@@ -160,6 +231,13 @@ pub enum AlertType {
     Lameness,
     Equipment,
     Status(StarterResult),
+    MovementDeviation(u8),
+    ScoreDeviation,
+}
+impl AlertType {
+    pub fn is_deviation(&self) -> bool {
+        matches!(self, Self::MovementDeviation(_) | Self::ScoreDeviation)
+    }
 }
 impl std::fmt::Display for AlertType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -176,6 +254,8 @@ impl std::fmt::Display for AlertType {
                 Blood => "Blood".to_string(),
                 Lameness => "Lameness".to_string(),
                 Equipment => "Equipment".to_string(),
+                MovementDeviation(n) => format!("Dev. {n}"),
+                ScoreDeviation => "Spread".to_string(),
                 Status(s) => match s {
                     Eliminated(_) => "Elim".to_string(),
                     Withdrawn => "WD".to_string(),
@@ -0,0 +1,109 @@
+use hypertext::Renderable;
+
+use crate::{
+    sockets::{
+        manager::ManagedSocket,
+        message_types::application::{AmendmentRequest, CompetitionMessage, Payload},
+    },
+    state::ManagedApplicationState,
+    templates::scoresheet::amendment::{amendment_form, amendment_status},
+};
+
+use super::replace_director::{PageLocation, ReplaceDirector, ResponseDirector};
+
+#[tauri::command]
+pub async fn show_amendment_form(
+    state: tauri::State<'_, ManagedApplicationState>,
+) -> ResponseDirector {
+    let can_amend = state
+        .read_async(|app_state| {
+            app_state
+                .scoresheet()
+                .is_some_and(|x| x.locked && !x.has_pending_amendment())
+        })
+        .await?;
+    let html = match can_amend {
+        true => amendment_form(None).render(),
+        false => amendment_form(Some("An amendment is already waiting for approval")).render(),
+    };
+    Ok(ReplaceDirector::with_target(
+        &PageLocation::AmendmentAside,
+        html,
+    ))
+}
+
+/// Asks for movements of a confirmed sheet to be opened again. The marks
+/// stay locked until the chief judge or scorer approves the request.
+#[tauri::command]
+pub async fn request_amendment(
+    state: tauri::State<'_, ManagedApplicationState>,
+    socket: tauri::State<'_, ManagedSocket>,
+    movements: Option<String>,
+    reason: Option<String>,
+) -> ResponseDirector {
+    let reason = reason.unwrap_or_default().trim().to_string();
+    let movements = movements
+        .unwrap_or_default()
+        .split([',', ' '])
+        .filter(|x| !x.is_empty())
+        .map(|x| x.parse::<u16>())
+        .collect::<Result<Vec<_>, _>>();
+    let invalid = |message: &str| {
+        Err(ReplaceDirector::with_target(
+            &PageLocation::AmendmentAside,
+            amendment_form(Some(message)).render(),
+        ))
+    };
+    let Ok(mut movements) = movements else {
+        return invalid("Movements should be numbers separated by commas");
+    };
+    movements.sort();
+    movements.dedup();
+    if movements.is_empty() {
+        return invalid("Choose at least one movement to amend");
+    }
+    if reason.is_empty() {
+        return invalid("Give a reason for the amendment");
+    }
+
+    let requested = state
+        .write_async(move |app_state| {
+            let known = app_state.get_test().map_or(vec![], |test| {
+                test.movements.iter().map(|x| x.number as u16).collect()
+            });
+            if let Some(unknown) = movements.iter().find(|x| !known.contains(x)) {
+                return Err(format!("There is no movement {unknown} on this test"));
+            }
+            let scoresheet = app_state
+                .scoresheet_mut()
+                .ok_or_else(|| String::from("There is no scoresheet to amend"))?;
+            if !scoresheet.locked {
+                return Err(String::from("The marks have not been confirmed yet"));
+            }
+            if scoresheet.has_pending_amendment() {
+                return Err(String::from("An amendment is already waiting for approval"));
+            }
+            let amendment = scoresheet.request_amendment(movements, reason);
+            Ok((scoresheet.id.ulid(), amendment))
+        })
+        .await?;
+    let (sheet_id, amendment) = match requested {
+        Ok(x) => x,
+        Err(message) => return invalid(&message),
+    };
+
+    let _ = socket
+        .send(Payload::Competition(CompetitionMessage::Amendment(
+            AmendmentRequest {
+                sheet_id,
+                id: amendment.id,
+                movements: amendment.movements.clone(),
+                reason: amendment.reason.clone(),
+            },
+        )))
+        .await;
+    Ok(ReplaceDirector::with_target(
+        &PageLocation::AmendmentAside,
+        amendment_status(Some(&amendment)).render(),
+    ))
+}
@@ -121,6 +121,17 @@ impl Timer {
     pub fn is_running_test_time(&self) -> bool {
         self.0.lock().expect("To be able to lock").test_time_running
     }
+    /// Stops every countdown. The counters are left where each countdown
+    /// finishes, so their loops end on the next tick.
+    pub fn stop(&self) {
+        let mut inner = self.0.lock().expect("To be able to lock");
+        inner.normal_running = false;
+        inner.music_running = false;
+        inner.test_time_running = false;
+        inner.normal_counter = 0;
+        inner.music_counter = 0;
+        inner.test_time_counter = -30;
+    }
     pub fn formatted_test_time(&self) -> String {
         let inner = self.0.lock().expect("To be able to lock");
         let minutes = i16::abs(inner.test_time_counter / 60);
@@ -11,7 +11,7 @@ use super::replace_director::ResponseDirector;
 pub async fn choose_starter(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
-    _handle: tauri::AppHandle,
+    handle: tauri::AppHandle,
     id: String,
 ) -> ResponseDirector {
     let starter = state
@@ -20,16 +20,19 @@ pub async fn choose_starter(
             let comp = app_state
                 .competition()
                 .ok_or_else(|| screen_error("Cannot find competition"))?;
-            let starter = comp.starters.iter().find(|x| x.get_id() == id).cloned();
+            let starter = comp.starters.iter().find(|x| x.get_id() == id);
+            let deviations = starter.map_or(vec![], |x| x.deviations(comp));
+            let starter = starter.cloned();
             app_state.starter_id = starter.as_ref().map(|x| x.id.clone());
             if app_state.starter_id.is_none() {
                 return Err(screen_error("Cannot find Starter for competition"));
             };
-            Ok(starter)
+            Ok(starter.map(|x| (x, deviations)))
         })
         .await??;
-    if let Some(ref starter) = starter {
+    if let Some((ref starter, ref deviations)) = starter {
         alert_manager.merge_starter(starter);
+        alert_manager.merge_deviations(deviations);
     }
-    templates::scoresheet::scoresheet(state, alert_manager).await
+    templates::scoresheet::scoresheet(state, alert_manager, handle).await
 }
@@ -0,0 +1,215 @@
+use std::io::Write;
+
+use hypertext::Renderable;
+use tauri::Manager;
+
+use crate::{
+    commands::warnings::broadcast::{broadcast_penalty, broadcast_status},
+    domain::{
+        edit_guard::EditRefused,
+        history::{History, SheetChange, SheetEvent},
+    },
+    sockets::{manager::ManagedSocket, message_types::application::Payload},
+    state::{ApplicationState, ManagedApplicationState},
+    templates::{
+        self,
+        scoresheet::{edit_refused_dialog, history::history_trail},
+    },
+};
+
+use super::alert_manager::AlertManager;
+use super::replace_director::{PageLocation, ReplaceDirector, ResponseDirector};
+
+#[tauri::command]
+pub async fn undo(
+    state: tauri::State<'_, ManagedApplicationState>,
+    alert_manager: tauri::State<'_, AlertManager>,
+    handle: tauri::AppHandle,
+) -> ResponseDirector {
+    let changes = state
+        .write_async(|app_state| {
+            let event = app_state.scoresheet()?.history.next_undo()?.clone();
+            if let Err(refusal) = check_event(app_state, &event) {
+                return Some(Err(refusal));
+            }
+            let test = app_state.get_test().cloned()?;
+            app_state.starter_mut()?.undo(&test).map(Ok)
+        })
+        .await?
+        .ok_or_else(ReplaceDirector::none)?
+        .map_err(refused)?;
+    send_changes(&handle, changes).await;
+    templates::scoresheet::scoresheet(state, alert_manager, handle).await
+}
+
+#[tauri::command]
+pub async fn redo(
+    state: tauri::State<'_, ManagedApplicationState>,
+    alert_manager: tauri::State<'_, AlertManager>,
+    handle: tauri::AppHandle,
+) -> ResponseDirector {
+    let changes = state
+        .write_async(|app_state| {
+            let event = app_state.scoresheet()?.history.next_redo()?.clone();
+            if let Err(refusal) = check_event(app_state, &event) {
+                return Some(Err(refusal));
+            }
+            let test = app_state.get_test().cloned()?;
+            app_state.starter_mut()?.redo(&test).map(Ok)
+        })
+        .await?
+        .ok_or_else(ReplaceDirector::none)?
+        .map_err(refused)?;
+    send_changes(&handle, changes).await;
+    templates::scoresheet::scoresheet(state, alert_manager, handle).await
+}
+
+/// Undoing or redoing an edit changes the sheet as much as making it, so
+/// every part of the sheet it touches must still be open to change
+fn check_event(app_state: &ApplicationState, event: &SheetEvent) -> Result<(), EditRefused> {
+    event
+        .changes
+        .iter()
+        .try_for_each(|change| app_state.check_edit(change.edit()))
+}
+
+fn refused(refusal: EditRefused) -> ReplaceDirector {
+    ReplaceDirector::with_target(
+        &PageLocation::MissingScoreAside,
+        edit_refused_dialog(&refusal).render(),
+    )
+}
+
+#[tauri::command]
+pub async fn show_history(state: tauri::State<'_, ManagedApplicationState>) -> ResponseDirector {
+    let history = current_history(&state).await?;
+    Ok(ReplaceDirector::with_target(
+        &PageLocation::HistoryAside,
+        history_trail(&history, None).render(),
+    ))
+}
+
+/// Writes the audit trail of the scoresheet to the documents directory,
+/// next to the log file.
+#[tauri::command]
+pub async fn export_history(
+    state: tauri::State<'_, ManagedApplicationState>,
+    handle: tauri::AppHandle,
+) -> ResponseDirector {
+    let (name, lines) = state
+        .read_async(|app_state| {
+            let test = app_state.get_test()?;
+            let starter = app_state.starter()?;
+            let sheet = starter.scoresheets.first()?;
+            let mut lines = vec![format!(
+                "{} {} - {}",
+                starter.competitor.comp_no,
+                starter.name(),
+                starter.horse()
+            )];
+            let undone_from = sheet.history.events.len() - sheet.history.undone;
+            for (i, event) in sheet.history.events.iter().enumerate() {
+                let changes = event
+                    .changes
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                let undone = if i >= undone_from { " (undone)" } else { "" };
+                lines.push(format!("@{} {changes}{undone}", event.at));
+            }
+            lines.push(String::from("Marks from history:"));
+            for scored in sheet.replay().scores.iter() {
+                lines.push(format!(
+                    "{}: {} {}",
+                    scored.number,
+                    scored
+                        .mark
+                        .map_or_else(|| "-".to_string(), |x| x.to_string()),
+                    scored.remark.as_deref().unwrap_or("")
+                ));
+            }
+            let report = sheet.deduction_report(test);
+            lines.push(format!(
+                "Deductions: -{}%",
+                report.precision.format(report.total())
+            ));
+            for penalty in report.penalties.iter() {
+                lines.push(penalty.to_string());
+            }
+            Some((format!("History {}.txt", sheet.id.id()), lines))
+        })
+        .await?
+        .ok_or_else(ReplaceDirector::none)?;
+
+    let message = match write_document(&handle, &name, &lines.join("\n")) {
+        Ok(()) => format!("Exported to {name} in your documents"),
+        Err(err) => format!("Could not export the history: {err}"),
+    };
+    let history = current_history(&state).await?;
+    Ok(ReplaceDirector::with_target(
+        &PageLocation::HistoryAside,
+        history_trail(&history, Some(message)).render(),
+    ))
+}
+
+async fn current_history(
+    state: &tauri::State<'_, ManagedApplicationState>,
+) -> Result<History, ReplaceDirector> {
+    state
+        .read_async(|app_state| app_state.scoresheet().map(|x| x.history.clone()))
+        .await?
+        .ok_or_else(ReplaceDirector::none)
+}
+
+fn write_document(handle: &tauri::AppHandle, name: &str, content: &str) -> std::io::Result<()> {
+    let mut path = handle
+        .path()
+        .document_dir()
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::NotFound, "No document directory"))?;
+    path.push(name);
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(content.as_bytes())
+}
+
+/// Sends the marks, penalties and status which an undo or redo put back to the server
+async fn send_changes(handle: &tauri::AppHandle, changes: Vec<SheetChange>) {
+    let state = handle.state::<ManagedApplicationState>();
+    let socket = handle.state::<ManagedSocket>();
+    for change in changes {
+        match change {
+            SheetChange::Mark { number, .. }
+            | SheetChange::Remark { number, .. }
+            | SheetChange::Attempts { number, .. } => {
+                let scored = state
+                    .read_async(move |app_state| {
+                        let sheet = app_state.scoresheet()?;
+                        let scored = sheet.scores.iter().find(|x| x.number == number)?;
+                        Some((sheet.id.ulid(), scored.mark, scored.remark.clone()))
+                    })
+                    .await;
+                if let Ok(Some((sheet_id, mark, remark))) = scored {
+                    let _ = socket
+                        .send(Payload::mark(sheet_id, number, mark, remark))
+                        .await;
+                }
+            }
+            SheetChange::Status { to, .. } => {
+                let sheet_id = state
+                    .read_async(|app_state| app_state.scoresheet().map(|x| x.id.ulid()))
+                    .await;
+                if let Ok(Some(sheet_id)) = sheet_id {
+                    broadcast_status(handle, sheet_id, to);
+                }
+            }
+            SheetChange::Penalty { source, to, .. } => {
+                let sheet_id = state
+                    .read_async(|app_state| app_state.scoresheet().map(|x| x.id.ulid()))
+                    .await;
+                if let Ok(Some(sheet_id)) = sheet_id {
+                    broadcast_penalty(handle, sheet_id, source.into(), to);
+                }
+            }
+        }
+    }
+}
@@ -10,6 +10,7 @@ pub async fn log_out(
     state: tauri::State<'_, ManagedApplicationState>,
     handle: tauri::AppHandle,
 ) -> ResponseDirector {
+    super::navigation::leave_competition(&handle).await;
     state
         .write_async(|x| *x = ApplicationState::new())
         .await
@@ -1,15 +1,18 @@
-use decimal::{dec, Decimal, RoundingMode};
-use std::str::FromStr;
+use decimal::{dec, Decimal};
 use tauri::Manager;
 
 use crate::{
     domain::{
-        dressage_test::{DressageTest, Exercise},
+        dressage_test::{DressageTest, Exercise, ParsedMark, Precision, TestSheetType},
+        edit_guard::{EditRefused, SheetEdit},
         scoresheet::{ScoredMark, Scoresheet},
     },
-    sockets::{manager::ManagedSocket, message_types::application::Payload},
+    sockets::{manager::ManagedSocket, message_types::application::Payload, outbox::ManagedOutbox},
     state::ManagedApplicationState,
-    templates::scoresheet::{attempt_input, attempt_input_with_score, get_attempt_buttons},
+    templates::scoresheet::{
+        attempt_input, attempt_input_with_score, difficulty_suggestion, edit_refused_dialog,
+        get_attempt_buttons,
+    },
 };
 
 use super::replace_director::{
@@ -17,12 +20,6 @@ use super::replace_director::{
     ResponseDirector,
 };
 
-enum MarkState {
-    Unprocessable,
-    OutOfBounds,
-    Incomplete(Decimal),
-    Complete(Decimal),
-}
 #[tauri::command]
 pub fn input_mark(
     state: tauri::State<'_, ManagedApplicationState>,
@@ -34,53 +31,36 @@ pub fn input_mark(
     state
         .read(move |app_state| app_state.score_debounces.cancel(index))
         .map_err(|_| "Err".to_string())?;
-    let final_mark: MarkState = 'bounds: {
-        if value.is_empty() {
-            break 'bounds MarkState::Unprocessable;
-        }
-        let bytes = value.as_bytes();
-        for char in bytes.iter() {
-            match char {
-                b'.' | b'-' | b'0'..=b'9' => (),
-                _ => break 'bounds MarkState::Unprocessable,
-            }
-        }
-        let movement = state
+    if guard_edit(&handle, SheetEdit::Movement(index)).is_err() {
+        // put the confirmed mark back in the input
+        return Ok(state
             .read(move |app_state| {
-                get_current_movement(app_state.get_test().expect("No test"), index)
+                let scored = app_state
+                    .scoresheet()?
+                    .scores
+                    .iter()
+                    .find(|x| x.number == index)?;
+                scored.mark.map(|x| x.to_string())
             })
-            .map_err(|_| "Err".to_string())?;
-
-        let Ok(mut mark) = Decimal::from_str(&value) else {
-            break 'bounds MarkState::Unprocessable;
-        };
-
-        // check that mark conforms to the movement requirements
-        if mark < movement.min || mark.scale() > movement.step.scale() {
-            break 'bounds MarkState::OutOfBounds;
-        }
-        let corrected_decimal_place = mark >= movement.max; // if it's equal, it also cannot have any
-                                                            // more added
-        while mark > movement.max {
-            mark = mark.safe_divide(dec!(10.0), 3).map_err(|_| String::new())?;
-        }
-        if mark % movement.step != dec!(0.0) {
-            break 'bounds MarkState::OutOfBounds;
-        }
-        if corrected_decimal_place {
-            MarkState::Complete(mark.to_precision(movement.step.scale()))
-        } else {
-            MarkState::Incomplete(mark.to_precision(movement.step.scale()))
-        }
-    };
+            .ok()
+            .flatten()
+            .unwrap_or_default());
+    }
+    let movement = state
+        .read(move |app_state| get_current_movement(app_state.get_test().expect("No test"), index))
+        .map_err(|_| "Err".to_string())?;
+    let final_mark = movement.mark_parser().parse(&value);
     match final_mark {
-        MarkState::Complete(mark) | MarkState::Incomplete(mark) => {
+        ParsedMark::Complete(mark) | ParsedMark::Incomplete(mark) => {
             let _ = state.write(move |app_state| {
-                let sheet = app_state
-                    .scoresheet_mut()
-                    .expect("Should be able to get scoresheet. Maybe shouldn't be an expect");
-                let score = get_current_scored_exercise_mut(sheet, index);
-                score.mark = Some(mark);
+                app_state.tracked(|app_state| {
+                    let sheet = app_state
+                        .scoresheet_mut()
+                        .expect("Should be able to get scoresheet. Maybe shouldn't be an expect");
+                    let score = get_current_scored_exercise_mut(sheet, index);
+                    score.mark = Some(mark);
+                    score.manually_set = !score.attempts.is_empty();
+                })
             });
             calculate_trend_and_emit(&handle);
         }
@@ -93,13 +73,13 @@ pub fn input_mark(
     // a blank string if a parse error
     let handle = handle.clone();
     Ok(match final_mark {
-        MarkState::Complete(mark) => {
+        ParsedMark::Complete(mark) => {
             tauri::async_runtime::spawn(async move {
                 parse_and_send_mark(handle, Some(mark), index).await;
             });
             mark.to_string()
         }
-        MarkState::Incomplete(mark) => {
+        ParsedMark::Incomplete(mark) => {
             _ = state.write(move |app_state| {
                 app_state.score_debounces.debounce(
                     index,
@@ -124,14 +104,15 @@ pub async fn assent_mark(
     index: &str,
 ) -> Result<String, String> {
     let index = index.parse::<u16>().expect("to get index");
-    let mark = Decimal::from_str(value).map_err(|_| String::new())?;
 
     let state = handle.state::<ManagedApplicationState>();
     let movement = state
         .read(move |app_state| get_current_movement(app_state.get_test().expect("No test"), index))
         .map_err(|_| String::new())?;
 
-    if mark >= movement.min && mark <= movement.max && mark % movement.step == dec!(0.0) {
+    if let ParsedMark::Complete(mark) | ParsedMark::Incomplete(mark) =
+        movement.mark_parser().parse(value)
+    {
         let _ = state.read(move |a| a.score_debounces.execute_immediately(index));
         return Ok(mark.to_string());
     }
@@ -139,19 +120,57 @@ pub async fn assent_mark(
 }
 fn calculate_trend_and_emit(handle: &tauri::AppHandle) {
     let state = handle.state::<ManagedApplicationState>();
-    let trend = state
+    let (trend, breakdown, rank, precision) = state
         .read(|app_state| {
             let scoresheet = app_state.scoresheet();
-            scoresheet.map_or(dec!(0.0), |x| {
+            let rank = app_state
+                .starter()
+                .and_then(|starter| app_state.rank(starter));
+            let precision = app_state
+                .get_test()
+                .map(|x| x.precision)
+                .unwrap_or_default();
+            scoresheet.map_or((dec!(0.0), None, rank, precision), |x| {
                 let test = app_state.get_test().expect("There must be a test");
-                x.calculate_trend(test)
+                (
+                    x.calculate_trend(test),
+                    x.trend_breakdown(test),
+                    rank,
+                    precision,
+                )
             })
         })
-        .ok();
-    let trend = crate::templates::scoresheet::header_trend(trend, Some(0), true);
+        .map_or(
+            (None, None, None, Precision::default()),
+            |(trend, breakdown, rank, precision)| (Some(trend), breakdown, rank, precision),
+        );
+    let trend = crate::templates::scoresheet::header_trend(trend, rank, true, breakdown, precision);
     let trend = hypertext::Renderable::render(&trend);
     emit_page_prerendered(&handle, &PageLocation::HeaderTrend, trend.clone());
     emit_page_prerendered(&handle, &PageLocation::TotalScore, trend);
+    emit_difficulty_suggestion(handle);
+}
+
+/// Freestyles suggest the degree of difficulty mark from the difficulties shown so far
+fn emit_difficulty_suggestion(handle: &tauri::AppHandle) {
+    let state = handle.state::<ManagedApplicationState>();
+    let evaluation = state
+        .read(|app_state| {
+            let test = app_state.get_test()?;
+            if test.test_type != TestSheetType::Freestyle {
+                return None;
+            }
+            Some(app_state.scoresheet()?.evaluate_difficulty(test))
+        })
+        .ok()
+        .flatten();
+    if let Some(evaluation) = evaluation {
+        emit_page(
+            handle,
+            &PageLocation::Any(".difficulty-suggestion".to_string()),
+            difficulty_suggestion(&evaluation),
+        );
+    }
 }
 
 pub async fn parse_and_send_mark(
@@ -165,18 +184,23 @@ pub async fn parse_and_send_mark(
     calculate_trend_and_emit(&handle);
     let (sheet_id, comment) = state
         .write_async(move |app_state| {
-            let sheet = app_state
-                .scoresheet_mut()
-                .expect("Should be able to get scoresheet. Maybe shouldn't be an expect");
-            let remark = {
-                let score = get_current_scored_exercise_mut(sheet, index);
-                score.mark = mark;
-                score.remark.clone()
-            };
-            (sheet.id.ulid(), remark)
+            // the sheet may have been confirmed while the mark was waiting
+            app_state.check_edit(SheetEdit::Movement(index)).ok()?;
+            app_state.tracked(|app_state| {
+                let sheet = app_state
+                    .scoresheet_mut()
+                    .expect("Should be able to get scoresheet. Maybe shouldn't be an expect");
+                let remark = {
+                    let score = get_current_scored_exercise_mut(sheet, index);
+                    score.mark = mark;
+                    score.remark.clone()
+                };
+                Some((sheet.id.ulid(), remark))
+            })
         })
         .await
-        .ok()?;
+        .ok()
+        .flatten()?;
     emit_page_prerendered(
         &handle,
         &PageLocation::Any(format!("tr [data-input-role='mark'][data-index='{index}']")),
@@ -192,31 +216,56 @@ pub async fn parse_and_send_mark(
 #[tauri::command]
 pub async fn input_comment(
     state: tauri::State<'_, ManagedApplicationState>,
+    handle: tauri::AppHandle,
     value: String,
     index: &str,
 ) -> Result<String, String> {
     let index = index.parse::<u16>().expect("Index should be parsable");
+    if guard_edit(&handle, SheetEdit::Movement(index)).is_err() {
+        return Err(String::new());
+    }
     state
         .write_async(move |app_state| {
-            let scored_exercise = get_current_scored_exercise_mut(
-                app_state
-                    .scoresheet_mut()
-                    .expect("Should have the scoresheet. Maybe this shouldn't be expect"),
-                index,
-            );
+            app_state.tracked(|app_state| {
+                let scored_exercise = get_current_scored_exercise_mut(
+                    app_state
+                        .scoresheet_mut()
+                        .expect("Should have the scoresheet. Maybe this shouldn't be expect"),
+                    index,
+                );
 
-            // otherwise, change mark to nothing and reset to user
-            scored_exercise.remark = if value.is_empty() {
-                None
-            } else {
-                Some(value.to_string())
-            };
+                // otherwise, change mark to nothing and reset to user
+                scored_exercise.remark = if value.is_empty() {
+                    None
+                } else {
+                    Some(value.to_string())
+                };
+            })
         })
         .await
         .ok();
     Ok(String::new())
 }
 
+/// Refuses edits the scoresheet no longer allows, telling the judge why
+pub(crate) fn guard_edit(handle: &tauri::AppHandle, edit: SheetEdit) -> Result<(), EditRefused> {
+    let state = handle.state::<ManagedApplicationState>();
+    let checked = match handle.state::<ManagedOutbox>().is_full() {
+        true => Err(EditRefused::OutboxFull),
+        false => state
+            .read(move |app_state| app_state.check_edit(edit))
+            .unwrap_or(Ok(())),
+    };
+    if let Err(ref refusal) = checked {
+        emit_page(
+            handle,
+            &PageLocation::MissingScoreAside,
+            edit_refused_dialog(refusal),
+        );
+    }
+    checked
+}
+
 fn get_current_movement(test: &DressageTest, index: u16) -> Exercise {
     test.movements
         .iter()
@@ -245,37 +294,33 @@ pub async fn input_attempt(
     attempt: &str,
 ) -> Result<String, String> {
     let index = index.parse::<u16>().expect("Index should be parsable");
+    if guard_edit(&handle, SheetEdit::Movement(index)).is_err() {
+        return Err(String::new());
+    }
 
-    let (min, max, step) = state
+    let movement = state
         .read_async(move |app_state| {
-            let movement = get_current_movement(app_state.get_test().expect("No test"), index);
-            (movement.min, movement.max, movement.step)
+            get_current_movement(app_state.get_test().expect("No test"), index)
         })
         .await
-        .map_err(|_| "Could not get movement triple".to_string())?;
-    // parse value
-    if value.ends_with(".") {
-        let trimmed = &value[0..value.len() - 1];
-        if trimmed.parse::<f64>().is_ok() {
-            return Ok(value.to_string());
+        .map_err(|_| "Could not get movement".to_string())?;
+    Ok(match movement.mark_parser().parse(value) {
+        ParsedMark::Complete(mark) => {
+            let confirmed = mark.to_string();
+            _ = confirm_attempt(
+                state.clone(),
+                handle,
+                &confirmed,
+                &index.to_string(),
+                attempt,
+            )
+            .await;
+            confirmed
         }
-    } else if let Ok(mut num) = <Decimal as std::str::FromStr>::from_str(value) {
-        if num >= min {
-            while num > max {
-                num /= max;
-            }
-            if num % step == dec![0.0] {
-                if value.len() >= 3 {
-                    _ = confirm_attempt(state.clone(), handle, value, &index.to_string(), attempt)
-                        .await;
-                }
-                //return to user
-                return Ok(format!("{num}"));
-            }
-        }
-    }
-    // otherwise, change mark to nothing and reset to user
-    Ok(String::new())
+        ParsedMark::Incomplete(_) => value.to_string(),
+        // otherwise, change mark to nothing and reset to user
+        ParsedMark::OutOfBounds | ParsedMark::Unparseable => String::new(),
+    })
 }
 #[tauri::command]
 pub async fn confirm_attempt(
@@ -287,6 +332,9 @@ pub async fn confirm_attempt(
 ) -> Result<String, String> {
     let index = index.parse::<u16>().expect("Index should be parsable");
     let attempt = attempt.parse::<usize>().expect("Index should be parsable");
+    if guard_edit(&handle, SheetEdit::Movement(index)).is_err() {
+        return Err(String::new());
+    }
     let (movement, mut scored_exercise) = state
         .write_async(move |app_state| {
             let movement = get_current_movement(app_state.get_test().expect("No test"), index);
@@ -300,52 +348,54 @@ pub async fn confirm_attempt(
         })
         .await
         .map_err(|_| String::new())?;
-    // parse value
-    if value.ends_with(".") {
-        let trimmed = &value[0..value.len() - 1];
-        if trimmed.parse::<f64>().is_ok() {
-            return Ok(value.to_string());
+    if value.is_empty() {
+        if attempt < scored_exercise.attempts.len() {
+            scored_exercise.attempts.swap_remove(attempt);
         }
-    } else if value.is_empty() && attempt < scored_exercise.attempts.len() {
-        scored_exercise.attempts.swap_remove(attempt);
-    } else if let Ok(mut num) = <Decimal as std::str::FromStr>::from_str(value) {
-        if num >= movement.min {
-            while num > movement.max {
-                num /= movement.max;
-            }
-            if num % movement.step == dec!(0.0) {
+    } else {
+        // confirming is deliberate, so a mark the judge could still have
+        // added to is taken as it stands
+        match movement.mark_parser().parse(value) {
+            ParsedMark::Complete(num) | ParsedMark::Incomplete(num) => {
                 if attempt < scored_exercise.attempts.len() {
                     scored_exercise.attempts[attempt] = num;
                 } else {
                     scored_exercise.attempts.push(num);
                 }
-            } else {
-                return Err(String::new());
             }
-        } else {
-            return Err(String::new());
+            ParsedMark::OutOfBounds | ParsedMark::Unparseable => return Err(String::new()),
         }
     }
 
-    // TODO: This should respect the correct "fair rounding" rounding
-    // techniques.
-    // 1. Take all marks which cannot be evenly averaged.
-    // 2. sort them by highest to lowest coefficient, then
-    // 3. sort them by movement number
-    // 4. alternately round up and down, starting with round up.
-    // 5. remove any marks which have been manually set, and don't update these marks.
-    //      This is important so that the judge doesn't have the marks dancing around
-    //      after already been rounded just because they manually set a mark.
-    // 6. Post updated marks back to the front-end.
-    let step_scale = movement.step.scale();
-    let preproccessed_mark =
-        Decimal::average(&scored_exercise.attempts, step_scale, RoundingMode::Up);
-    scored_exercise.mark = preproccessed_mark;
+    // Changing the attempts is a request to average them again, so any
+    // manually set mark on this movement is released back to fair rounding.
+    // Every other movement is then re-rounded, since the alternating
+    // up/down order depends on the whole scoresheet.
+    let attempts = scored_exercise.attempts.clone();
+    let (scored_exercise, changed, sheet_id) = state
+        .write_async(move |app_state| {
+            app_state.tracked(|app_state| {
+                let test = app_state.get_test().cloned().expect("No test");
+                let sheet = app_state
+                    .scoresheet_mut()
+                    .expect("Scoresheet should exist. Maybe shouldn't be expect");
+                let scored_exercise = get_current_scored_exercise_mut(sheet, index);
+                scored_exercise.attempts = attempts;
+                scored_exercise.manually_set = false;
+                if scored_exercise.attempts.is_empty() {
+                    scored_exercise.mark = None;
+                }
+                let mut changed = sheet.fair_round_attempts(&test);
+                if !changed.iter().any(|x| x.number == index) {
+                    changed.push(get_current_scored_exercise_mut(sheet, index).clone());
+                }
+                let scored_exercise = get_current_scored_exercise_mut(sheet, index).clone();
+                (scored_exercise, changed, sheet.id.ulid())
+            })
+        })
+        .await
+        .map_err(|_| String::new())?;
 
-    // calculate
-    let export_mark = scored_exercise
-        .mark
-        .map_or_else(String::new, |x| x.to_string());
     emit_page(
         &handle,
         &PageLocation::Any(format!("tr[data-index='{index}'] .attempt-track")),
@@ -358,28 +408,26 @@ pub async fn confirm_attempt(
         )),
         attempt_input(index as u8, scored_exercise.attempts.len()),
     );
-    emit_page_prerendered(
-        &handle,
-        &PageLocation::Any(format!(
-            "tr[data-index='{index}'] input.exercise-input[data-input-role='mark']",
-        )),
-        hypertext::Rendered(export_mark),
-    );
-    //trend
-    let trend = state
-        .read_async(|app_state| {
-            let scoresheet = app_state
-                .scoresheet()
-                .cloned()
-                .expect("There to be a scoresheet");
-            scoresheet.calculate_trend(app_state.get_test().expect("No test"))
-        })
-        .await
-        .ok();
-    let trend = crate::templates::scoresheet::header_trend(trend, Some(0), true);
-    let trend = hypertext::Renderable::render(&trend);
-    emit_page_prerendered(&handle, &PageLocation::HeaderTrend, trend.clone());
-    emit_page_prerendered(&handle, &PageLocation::TotalScore, trend);
+    let socket = handle.state::<ManagedSocket>();
+    for scored in changed.into_iter() {
+        emit_page_prerendered(
+            &handle,
+            &PageLocation::Any(format!(
+                "tr[data-index='{}'] input.exercise-input[data-input-role='mark']",
+                scored.number
+            )),
+            hypertext::Rendered(scored.mark.map_or_else(String::new, |x| x.to_string())),
+        );
+        let _ = socket
+            .send(Payload::mark(
+                sheet_id,
+                scored.number,
+                scored.mark,
+                scored.remark,
+            ))
+            .await;
+    }
+    calculate_trend_and_emit(&handle);
 
     Ok(String::new())
 }
@@ -391,6 +439,9 @@ pub async fn edit_attempt(
     attempt: usize,
     index: u16,
 ) -> ResponseDirector {
+    if guard_edit(&handle, SheetEdit::Movement(index)).is_err() {
+        return Err(ReplaceDirector::none());
+    }
     let attempt_score = state
         .write_async(move |x| {
             let scored_exercise = get_current_scored_exercise_mut(
@@ -1,7 +1,9 @@
 pub mod alert_manager;
+pub mod amendment;
 pub mod bell_timer;
 pub mod choose_starter;
 pub mod fetch;
+pub mod history;
 pub mod log_out;
 pub mod logins;
 pub mod mark_comment;
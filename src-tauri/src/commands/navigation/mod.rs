@@ -16,7 +16,7 @@ use crate::{
         show::{Show, Shows},
         starter::Starter,
     },
-    sockets::{manager::ManagedSocket, message_types::application},
+    sockets::subscriptions::ManagedSubscriptions,
     state::{store::Storable, ManagedApplicationState},
     templates::{self, error::screen_error},
     traits::{Entity, Fetchable},
@@ -27,6 +27,7 @@ pub async fn page_x_judge_login(
     state: tauri::State<'_, ManagedApplicationState>,
     handle: tauri::AppHandle,
 ) -> ResponseDirector {
+    leave_competition(&handle).await;
     super::super::templates::choose_judge::choose_judge(state, handle).await
 }
 
@@ -36,6 +37,7 @@ pub async fn page_x_welcome(
     handle: tauri::AppHandle,
 ) -> ResponseDirector {
     // TODO: update state and store show list
+    leave_competition(&handle).await;
     super::super::templates::welcome::welcome(state, handle).await
 }
 
@@ -44,6 +46,7 @@ pub async fn page_x_preferences(
     state: tauri::State<'_, ManagedApplicationState>,
     handle: tauri::AppHandle,
 ) -> ResponseDirector {
+    leave_competition(&handle).await;
     match super::super::templates::preferences::get_preferences(state.clone(), handle).await {
         Ok(page) => {
             state.write(|x| x.page = ApplicationPage::Preferences)?;
@@ -57,6 +60,7 @@ pub async fn page_x_settings(
     state: tauri::State<'_, ManagedApplicationState>,
     handle: tauri::AppHandle,
 ) -> ResponseDirector {
+    leave_competition(&handle).await;
     match super::super::templates::settings::get_settings(state.clone(), handle).await {
         Ok(page) => {
             state.write(|x| x.page = ApplicationPage::Settings)?;
@@ -85,6 +89,7 @@ pub async fn page_x_competition_list(
     handle: tauri::AppHandle,
     id: String,
 ) -> ResponseDirector {
+    leave_competition(&handle).await;
     let id2 = id.clone();
     let id3 = id.clone();
     let id4 = id.clone();
@@ -116,17 +121,15 @@ pub async fn page_x_competition_list(
 #[tauri::command]
 pub async fn page_x_scoresheet(
     state: tauri::State<'_, ManagedApplicationState>,
-    soc_man: tauri::State<'_, ManagedSocket>,
     alert_manager: tauri::State<'_, AlertManager>,
-    _handle: tauri::AppHandle,
+    handle: tauri::AppHandle,
     id: String,
 ) -> ResponseDirector {
-    if let Err(err) = soc_man
-        .send(application::Payload::Subscribe {
-            competition_id: ulid::Ulid::from_str(&id).map_err(|_| {
-                screen_error("Competition ID was not in expected format. Should be ULID")
-            })?,
-        })
+    let competition_id = ulid::Ulid::from_str(&id)
+        .map_err(|_| screen_error("Competition ID was not in expected format. Should be ULID"))?;
+    if let Err(err) = handle
+        .state::<ManagedSubscriptions>()
+        .subscribe(&handle, competition_id)
         .await
     {
         debug!(red, "{err:?}");
@@ -159,7 +162,7 @@ pub async fn page_x_scoresheet(
             })
             .await?;
 
-        crate::templates::scoresheet::scoresheet(state, alert_manager).await
+        crate::templates::scoresheet::scoresheet(state, alert_manager, handle).await
     } else {
         Err(screen_error("No starters in this competition"))
     }
@@ -181,10 +184,46 @@ pub async fn page_x_current(
         ApplicationPage::CompetitionList => {
             competition_list::competition_list(state, handle, String::from("TODO")).await
         }
-        ApplicationPage::Scoresheet(_) => scoresheet::scoresheet(state, alert_manager).await,
+        ApplicationPage::Scoresheet(_) => {
+            // the app was reopened on the scoresheet, so follow it again
+            let competition_id = state
+                .read_async(|x| x.competition_id.as_ref().map(|x| x.ulid()))
+                .await?;
+            if let Some(competition_id) = competition_id {
+                if let Err(err) = handle
+                    .state::<ManagedSubscriptions>()
+                    .subscribe(&handle, competition_id)
+                    .await
+                {
+                    debug!(red, "{err:?}");
+                }
+            }
+            scoresheet::scoresheet(state, alert_manager, handle).await
+        }
         ApplicationPage::Settings => settings::get_settings(state, handle).await,
         ApplicationPage::Preferences => preferences::get_preferences(state, handle).await,
         ApplicationPage::FinalResult => result::result(state).await,
         ApplicationPage::Error => Err(screen_error("Unspecified Error")),
     }
 }
+
+/// The judge no longer needs the trends and locks of a competition once
+/// they leave its scoresheet
+pub(crate) async fn leave_competition(handle: &tauri::AppHandle) {
+    let competition_id = handle
+        .state::<ManagedApplicationState>()
+        .read_async(|x| x.competition_id.as_ref().map(|x| x.ulid()))
+        .await
+        .ok()
+        .flatten();
+    let Some(competition_id) = competition_id else {
+        return;
+    };
+    if let Err(err) = handle
+        .state::<ManagedSubscriptions>()
+        .unsubscribe(handle, competition_id)
+        .await
+    {
+        debug!(red, "{err:?}");
+    }
+}
@@ -39,7 +39,7 @@ pub async fn recover(
             .await
         }
         Scoresheet(_) | FinalResult => {
-            templates::scoresheet::scoresheet(state, alert_manager).await
+            templates::scoresheet::scoresheet(state, alert_manager, handle).await
         }
     }
 }
@@ -89,6 +89,12 @@ pub(crate) enum PageLocation {
     CompetitionList,
     ButtonLameness,
     MissingScoreAside,
+    HistoryControls,
+    HistoryAside,
+    AmendmentAside,
+    StarterNames,
+    StarterNotice,
+    ConnectionIndicator,
     ButtonBlood,
     ButtonEquipment,
     ButtonMeeting,
@@ -97,6 +103,7 @@ pub(crate) enum PageLocation {
     PenaltiesErrors,
     PenaltiesTechnical,
     PenaltiesArtistic,
+    Deductions,
     FinalRemark,
     AlertsAndWarnings,
     LoginButton,
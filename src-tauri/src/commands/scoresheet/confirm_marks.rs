@@ -4,11 +4,14 @@ use tauri::Manager;
 use crate::commands::replace_director::{
     emit_page, emit_page_outer, PageLocation, ReplaceDirector, ResponseDirector,
 };
+use crate::domain::dressage_test::Counting;
 use crate::sockets::manager::ManagedSocket;
 use crate::sockets::message_types::{application, common};
 use crate::state::ManagedApplicationState;
+use crate::templates::scoresheet::amendment::amendment_status;
 use crate::templates::scoresheet::{
-    get_confirm_or_signature, get_main_mark_input, missing_movements_dialog, zip_exercise_and_marks,
+    get_confirm_or_signature, get_main_mark_input, missing_movements_dialog,
+    missing_remarks_dialog, zip_exercise_and_marks,
 };
 
 #[tauri::command]
@@ -16,19 +19,26 @@ pub async fn confirm_marks<'x>(
     state: tauri::State<'x, ManagedApplicationState>,
     handle: tauri::AppHandle,
 ) -> ResponseDirector {
-    let sheet_id = state
+    let (sheet_id, amended) = state
         .write_async(|app_state| {
-            let movements = app_state
+            let test = app_state
                 .get_test()
                 .ok_or_else(ReplaceDirector::none)?
-                .movements
                 .clone();
+            let policy = app_state
+                .competition()
+                .map(|x| x.remark_policy.clone())
+                .unwrap_or_default();
             let starter = app_state.starter_mut().ok_or_else(ReplaceDirector::none)?;
 
             match starter.scoresheets.first_mut() {
                 Some(scoresheet) => {
                     let mut unscored_movements = vec![];
-                    movements.iter().for_each(|movement| {
+                    test.movements.iter().for_each(|movement| {
+                        // optional movements can be left without a mark
+                        if movement.category.counting() == Counting::WhenMarked {
+                            return;
+                        }
                         let has_mark_scored = scoresheet
                             .scores
                             .iter()
@@ -38,9 +48,7 @@ pub async fn confirm_marks<'x>(
                             unscored_movements.push(movement.number);
                         };
                     });
-                    if unscored_movements.is_empty() {
-                        scoresheet.locked = true;
-                    } else {
+                    if !unscored_movements.is_empty() {
                         let movements = unscored_movements
                             .iter()
                             .map(|x| x.to_string())
@@ -50,7 +58,21 @@ pub async fn confirm_marks<'x>(
                             missing_movements_dialog(movements).render(),
                         ));
                     }
-                    Ok(scoresheet.id.ulid())
+                    let unremarked_movements = scoresheet.missing_remarks(&test, &policy);
+                    if !unremarked_movements.is_empty() {
+                        return Err(ReplaceDirector::with_target(
+                            &PageLocation::MissingScoreAside,
+                            missing_remarks_dialog(unremarked_movements).render(),
+                        ));
+                    }
+                    // confirming a locked sheet closes any approved amendments
+                    let amended = match scoresheet.locked {
+                        true => scoresheet.apply_amendments(),
+                        false => vec![],
+                    };
+                    scoresheet.locked = true;
+                    scoresheet.score = Some(scoresheet.calculate_score(&test));
+                    Ok((scoresheet.id.ulid(), amended))
                 }
                 None => Err(ReplaceDirector::none()),
             }
@@ -58,6 +80,7 @@ pub async fn confirm_marks<'x>(
         .await??;
 
     let app_handle = handle.clone();
+    let scores = (!amended.is_empty()).then_some(amended);
     tauri::async_runtime::spawn(async move {
         let manager = app_handle.try_state::<ManagedSocket>();
         if let Some(ref manager) = manager {
@@ -66,14 +89,14 @@ pub async fn confirm_marks<'x>(
                     application::CompetitionMessage::Lock(common::Lock {
                         locked: true,
                         sheet_id,
-                        scores: None,
+                        scores,
                     }),
                 ))
                 .await
                 .map_err(|_| ReplaceDirector::none());
         };
     });
-    let (scores, signature) = state
+    let (scores, signature, amendment) = state
         .read_async(|app_state| {
             let competition = app_state.competition().ok_or_else(ReplaceDirector::none)?;
             let judge = competition.jury.first().ok_or_else(ReplaceDirector::none)?;
@@ -85,6 +108,7 @@ pub async fn confirm_marks<'x>(
             Ok((
                 zip_exercise_and_marks(test.movements.clone(), scoresheet.scores.clone()),
                 judge.judge.signature.clone(),
+                scoresheet.amendments.last().cloned(),
             ))
         })
         .await??;
@@ -98,8 +122,13 @@ pub async fn confirm_marks<'x>(
             get_main_mark_input(&mark, &exercise, true),
         );
     }
+    emit_page(
+        &handle,
+        &PageLocation::AmendmentAside,
+        amendment_status(amendment.as_ref()),
+    );
     Ok(ReplaceDirector::with_target(
         &PageLocation::ConfirmMarks,
-        get_confirm_or_signature(true, signature).render(),
+        get_confirm_or_signature(true, false, signature).render(),
     ))
 }
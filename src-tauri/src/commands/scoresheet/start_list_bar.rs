@@ -26,7 +26,8 @@ pub async fn filter_starters(
                 .first()
                 .ok_or_else(|| screen_error("Judge not found"))?;
 
-            Ok(get_starters_list(&competition.starters, current_starter_id, judge, value).render())
+            let ranking = competition.ranking();
+            Ok(get_starters_list(competition, current_starter_id, judge, &ranking, value).render())
         })
         .await??;
 
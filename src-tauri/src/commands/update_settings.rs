@@ -4,10 +4,10 @@ use crate::templates::error::screen_error;
 use crate::templates::settings::button_freestyle_mode;
 use hypertext::rsx;
 use hypertext::Renderable;
-use tauri_plugin_store::StoreExt;
+use tauri::Manager;
 
 use crate::{
-    sockets::manager::STORED_MESSAGES,
+    sockets::outbox::ManagedOutbox,
     state::{application_page::ApplicationPage, ApplicationState},
     templates::settings::clear_data_button,
 };
@@ -62,8 +62,9 @@ pub fn clear_data(
                 .render(),
             )
         })?;
-    let store = handle.store(env!("STORE_URI")).expect("To get the store");
-    _ = store.delete(STORED_MESSAGES);
+    handle
+        .state::<ManagedOutbox>()
+        .update(&handle, |x| x.clear());
     Ok(ReplaceDirector::with_target(
         &PageLocation::ClearDataButton,
         clear_data_button(true).render(),
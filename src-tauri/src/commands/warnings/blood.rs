@@ -4,8 +4,11 @@ use hypertext::{rsx_static, Rendered};
 use crate::{
     commands::{
         alert_manager::{AlertManager, AlertType},
+        mark_comment::guard_edit,
         replace_director::{emit_page, PageLocation, ReplaceDirector, ResponseDirector},
+        warnings::broadcast::broadcast_signal,
     },
+    domain::edit_guard::SheetEdit,
     state::ManagedApplicationState,
     templates::scoresheet::warnings::get_warnings,
 };
@@ -18,6 +21,9 @@ pub fn toggle_blood(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
+    if guard_edit(&app, SheetEdit::Signal).is_err() {
+        return Err(ReplaceDirector::none());
+    }
     Ok(ReplaceDirector::with_target(
         TARGET,
         match state.write(|app_state| {
@@ -29,6 +35,7 @@ pub fn toggle_blood(
             if let Some(s) = app_state.scoresheet_mut() {
                 let val = s.warning_manager.blood.toggle(&position);
                 alert_manager.set(AlertType::Blood, &position, val);
+                broadcast_signal(&app, s.id.ulid(), AlertType::Blood, val);
                 emit_page(&app, ALERT_TARGET, get_warnings(alert_manager));
                 val
             } else {
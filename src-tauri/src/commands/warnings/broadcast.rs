@@ -0,0 +1,68 @@
+use tauri::Manager;
+
+use crate::{
+    commands::alert_manager::AlertType,
+    domain::{penalties::BroadcastPenaltyVariety, position::Position, starter::StarterResult},
+    sockets::{
+        manager::ManagedSocket,
+        message_types::{
+            application::{self, CompetitionMessage},
+            common,
+        },
+    },
+    state::ManagedApplicationState,
+};
+
+/// Sends a change the judge made to the scorer and the rest of the jury,
+/// marked with the position the judge sits at. The position is looked up
+/// once the command has let go of the state.
+fn broadcast(
+    handle: &tauri::AppHandle,
+    message: impl FnOnce(Position) -> CompetitionMessage + Send + 'static,
+) {
+    let handle = handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let position = handle
+            .state::<ManagedApplicationState>()
+            .read_async(|app_state| app_state.competition().and_then(|x| x.get_position()))
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        if let Some(socket) = handle.try_state::<ManagedSocket>() {
+            _ = socket
+                .send(application::Payload::Competition(message(position)))
+                .await;
+        }
+    });
+}
+
+pub fn broadcast_status(handle: &tauri::AppHandle, sheet_id: ulid::Ulid, status: StarterResult) {
+    broadcast(handle, move |position| {
+        CompetitionMessage::Status(common::Status::new(sheet_id, status, position))
+    });
+}
+
+pub fn broadcast_signal(
+    handle: &tauri::AppHandle,
+    sheet_id: ulid::Ulid,
+    signal: AlertType,
+    active: bool,
+) {
+    broadcast(handle, move |position| {
+        CompetitionMessage::Signal(common::Signal::new(sheet_id, signal, active, position))
+    });
+}
+
+pub fn broadcast_penalty(
+    handle: &tauri::AppHandle,
+    sheet_id: ulid::Ulid,
+    variety: BroadcastPenaltyVariety,
+    quantity: u8,
+) {
+    broadcast(handle, move |position| {
+        CompetitionMessage::Penalty(application::Penalty::new(
+            sheet_id, variety, quantity, position,
+        ))
+    });
+}
@@ -1,8 +1,11 @@
 use crate::{
     commands::{
         alert_manager::{AlertManager, AlertType},
+        mark_comment::guard_edit,
         replace_director::{emit_page, PageLocation},
+        warnings::broadcast::broadcast_signal,
     },
+    domain::edit_guard::SheetEdit,
     templates::{html_elements, scoresheet::warnings::get_warnings},
 };
 use hypertext::{rsx_static, Renderable};
@@ -20,6 +23,9 @@ pub fn toggle_lameness(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
+    if guard_edit(&app, SheetEdit::Signal).is_err() {
+        return Err(ReplaceDirector::none());
+    }
     Ok(ReplaceDirector::with_target(
         TARGET,
         match state.write(|app_state| {
@@ -31,6 +37,7 @@ pub fn toggle_lameness(
             if let Some(s) = app_state.scoresheet_mut() {
                 let val = s.warning_manager.lameness.toggle(&position);
                 alert_manager.set(AlertType::Lameness, &position, val);
+                broadcast_signal(&app, s.id.ulid(), AlertType::Lameness, val);
                 emit_page(&app, ALERT_TARGET, get_warnings(alert_manager));
                 val
             } else {
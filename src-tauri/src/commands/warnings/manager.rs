@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use crate::commands::alert_manager::AlertType;
 use crate::domain::{position::Position, starter::StarterResult};
 
 //TODO: Need to account for how other judges can be prefilled into this
@@ -41,6 +42,35 @@ impl Default for Warnings {
         }
     }
 }
+impl Warnings {
+    /// Where the alert is recorded against each position of the jury.
+    /// Deviations are worked out on the device, so they are not kept here.
+    pub fn get_mut(&mut self, alert: &AlertType) -> Option<&mut PositionedWarning> {
+        match alert {
+            AlertType::Blood => Some(&mut self.blood),
+            AlertType::Lameness => Some(&mut self.lameness),
+            AlertType::Equipment => Some(&mut self.equipement),
+            AlertType::Meeting => Some(&mut self.meeting),
+            AlertType::ErrorOfCourse(n) => Some(self.errors.entry(*n).or_default()),
+            AlertType::TechnicalPenalty(n) => Some(self.tech_penalties.entry(*n).or_default()),
+            AlertType::ArtisticPenalty(n) => Some(self.art_penalties.entry(*n).or_default()),
+            AlertType::Status(status) => Some(self.status.entry(status.to_string()).or_default()),
+            AlertType::MovementDeviation(_) | AlertType::ScoreDeviation => None,
+        }
+    }
+    /// Records the status the judge at the position gave the starter, which
+    /// replaces whatever status they gave before
+    pub fn set_status(&mut self, position: &Position, status: &StarterResult) {
+        for warning in self.status.values_mut() {
+            warning.set(position.clone(), false);
+        }
+        self.status
+            .entry(status.to_string())
+            .or_default()
+            .set(position.clone(), true);
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Default, Clone, Debug)]
 pub struct PositionedWarning {
     k: bool,
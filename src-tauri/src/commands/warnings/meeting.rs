@@ -1,7 +1,10 @@
 use hypertext::{rsx_static, Renderable};
 
 use crate::commands::alert_manager::{AlertManager, AlertType};
+use crate::commands::mark_comment::guard_edit;
 use crate::commands::replace_director::{emit_page, PageLocation};
+use crate::commands::warnings::broadcast::broadcast_signal;
+use crate::domain::edit_guard::SheetEdit;
 use crate::templates::html_elements;
 use crate::templates::scoresheet::warnings::get_warnings;
 use crate::{
@@ -17,6 +20,9 @@ pub fn toggle_meeting(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
+    if guard_edit(&app, SheetEdit::Signal).is_err() {
+        return Err(ReplaceDirector::none());
+    }
     Ok(ReplaceDirector::with_target(
         TARGET,
         match state.write(|app_state| {
@@ -28,6 +34,7 @@ pub fn toggle_meeting(
             if let Some(s) = app_state.scoresheet_mut() {
                 let val = s.warning_manager.meeting.toggle(&position);
                 alert_manager.set(AlertType::Meeting, &position, val);
+                broadcast_signal(&app, s.id.ulid(), AlertType::Meeting, val);
                 emit_page(&app, ALERT_TARGET, get_warnings(alert_manager));
                 val
             } else {
@@ -1,7 +1,8 @@
-pub mod manager;
 pub mod blood;
-pub mod lameness;
+pub mod broadcast;
 pub mod equipement;
+pub mod lameness;
+pub mod manager;
 pub mod meeting;
 pub mod penalties;
-pub mod status;
\ No newline at end of file
+pub mod status;
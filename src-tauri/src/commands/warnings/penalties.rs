@@ -1,16 +1,24 @@
 use crate::commands::replace_director::PageLocation;
+use crate::domain::edit_guard::SheetEdit;
+use crate::domain::penalties::BroadcastPenaltyVariety;
 use crate::domain::position::Position::{self, C};
 use crate::domain::scoresheet::Scoresheet;
+use crate::domain::starter::StarterResult;
 use crate::state::ApplicationState;
 use crate::{
     commands::{
         alert_manager::{AlertManager, AlertType},
+        mark_comment::guard_edit,
         replace_director::{emit_page, ReplaceDirector, ResponseDirector},
+        warnings::broadcast::{broadcast_penalty, broadcast_status},
     },
     state::ManagedApplicationState,
     templates::{
         error::screen_error,
-        scoresheet::{artistic_row, errors_row, technical_row, warnings::get_warnings},
+        scoresheet::{
+            artistic_row, deduction_report, edit_refused_dialog, errors_row, status_selection,
+            technical_row, warnings::get_warnings,
+        },
     },
 };
 use hypertext::Renderable;
@@ -18,6 +26,8 @@ const TARGET_NAME: &PageLocation = &PageLocation::AlertsAndWarnings;
 const PENALTIES: &PageLocation = &PageLocation::PenaltiesErrors;
 const TECHNICAL: &PageLocation = &PageLocation::PenaltiesTechnical;
 const ARTISTIC: &PageLocation = &PageLocation::PenaltiesArtistic;
+const STATUS: &PageLocation = &PageLocation::StatusSelector;
+const DEDUCTIONS: &PageLocation = &PageLocation::Deductions;
 
 // ERRORS
 #[tauri::command]
@@ -26,14 +36,29 @@ pub fn plus_error(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
-    let errors = state.write(|app_state| {
-        let position = get_position(&mut *app_state);
-        let scoresheet = get_scoresheet(&mut *app_state)?;
+    guard_penalty(&app)?;
+    let (errors, change, sheet_id) = state.write(|app_state| {
+        app_state.tracked(|app_state| {
+            let position = get_position(&mut *app_state);
+            let previous = current_elimination(app_state);
+            let scoresheet = get_scoresheet(&mut *app_state)?;
 
-        scoresheet.errors += 1;
-        alert_manager.toggle(AlertType::ErrorOfCourse(scoresheet.errors), &position);
-        Ok(scoresheet.errors)
+            scoresheet.errors += 1;
+            alert_manager.toggle(AlertType::ErrorOfCourse(scoresheet.errors), &position);
+            let errors = scoresheet.errors;
+            let sheet_id = scoresheet.id.ulid();
+            let change = update_elimination(app_state, previous, &alert_manager, &position);
+            Ok((errors, change, sheet_id))
+        })
     })??;
+    broadcast_penalty(
+        &app,
+        sheet_id,
+        BroadcastPenaltyVariety::ErrorsOfCourse,
+        errors,
+    );
+    emit_status_change(&app, change);
+    emit_deductions(&app, &state);
     emit_page(&app, TARGET_NAME, get_warnings(alert_manager));
     Ok(ReplaceDirector::with_target(
         PENALTIES,
@@ -46,14 +71,29 @@ pub fn sub_error(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
-    let errors = state.write(|app_state| {
-        let position = get_position(&mut *app_state);
-        let scoresheet = get_scoresheet(&mut *app_state)?;
+    guard_penalty(&app)?;
+    let (errors, change, sheet_id) = state.write(|app_state| {
+        app_state.tracked(|app_state| {
+            let position = get_position(&mut *app_state);
+            let previous = current_elimination(app_state);
+            let scoresheet = get_scoresheet(&mut *app_state)?;
 
-        alert_manager.toggle(AlertType::ErrorOfCourse(scoresheet.errors), &position);
-        scoresheet.errors = scoresheet.errors.saturating_sub(1);
-        Ok(scoresheet.errors)
+            alert_manager.toggle(AlertType::ErrorOfCourse(scoresheet.errors), &position);
+            scoresheet.errors = scoresheet.errors.saturating_sub(1);
+            let errors = scoresheet.errors;
+            let sheet_id = scoresheet.id.ulid();
+            let change = update_elimination(app_state, previous, &alert_manager, &position);
+            Ok((errors, change, sheet_id))
+        })
     })??;
+    broadcast_penalty(
+        &app,
+        sheet_id,
+        BroadcastPenaltyVariety::ErrorsOfCourse,
+        errors,
+    );
+    emit_status_change(&app, change);
+    emit_deductions(&app, &state);
     emit_page(&app, TARGET_NAME, get_warnings(alert_manager));
     Ok(ReplaceDirector::with_target(
         PENALTIES,
@@ -68,17 +108,32 @@ pub fn plus_technical(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
-    let tech_penalties = state.write(|app_state| {
-        let position = get_position(&mut *app_state);
-        let scoresheet = get_scoresheet(&mut *app_state)?;
+    guard_penalty(&app)?;
+    let (tech_penalties, change, sheet_id) = state.write(|app_state| {
+        app_state.tracked(|app_state| {
+            let position = get_position(&mut *app_state);
+            let previous = current_elimination(app_state);
+            let scoresheet = get_scoresheet(&mut *app_state)?;
 
-        scoresheet.tech_penalties += 1;
-        alert_manager.toggle(
-            AlertType::TechnicalPenalty(scoresheet.tech_penalties),
-            &position,
-        );
-        Ok(scoresheet.tech_penalties)
+            scoresheet.tech_penalties += 1;
+            alert_manager.toggle(
+                AlertType::TechnicalPenalty(scoresheet.tech_penalties),
+                &position,
+            );
+            let tech_penalties = scoresheet.tech_penalties;
+            let sheet_id = scoresheet.id.ulid();
+            let change = update_elimination(app_state, previous, &alert_manager, &position);
+            Ok((tech_penalties, change, sheet_id))
+        })
     })??;
+    broadcast_penalty(
+        &app,
+        sheet_id,
+        BroadcastPenaltyVariety::TechnicalPenalty,
+        tech_penalties,
+    );
+    emit_status_change(&app, change);
+    emit_deductions(&app, &state);
     emit_page(&app, TARGET_NAME, get_warnings(alert_manager));
     Ok(ReplaceDirector::with_target(
         TECHNICAL,
@@ -91,17 +146,32 @@ pub fn sub_technical(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
-    let tech_penalties = state.write(|app_state| {
-        let position = get_position(&mut *app_state);
-        let scoresheet = get_scoresheet(&mut *app_state)?;
+    guard_penalty(&app)?;
+    let (tech_penalties, change, sheet_id) = state.write(|app_state| {
+        app_state.tracked(|app_state| {
+            let position = get_position(&mut *app_state);
+            let previous = current_elimination(app_state);
+            let scoresheet = get_scoresheet(&mut *app_state)?;
 
-        alert_manager.toggle(
-            AlertType::TechnicalPenalty(scoresheet.tech_penalties),
-            &position,
-        );
-        scoresheet.tech_penalties = scoresheet.tech_penalties.saturating_sub(1);
-        Ok(scoresheet.tech_penalties)
+            alert_manager.toggle(
+                AlertType::TechnicalPenalty(scoresheet.tech_penalties),
+                &position,
+            );
+            scoresheet.tech_penalties = scoresheet.tech_penalties.saturating_sub(1);
+            let tech_penalties = scoresheet.tech_penalties;
+            let sheet_id = scoresheet.id.ulid();
+            let change = update_elimination(app_state, previous, &alert_manager, &position);
+            Ok((tech_penalties, change, sheet_id))
+        })
     })??;
+    broadcast_penalty(
+        &app,
+        sheet_id,
+        BroadcastPenaltyVariety::TechnicalPenalty,
+        tech_penalties,
+    );
+    emit_status_change(&app, change);
+    emit_deductions(&app, &state);
     emit_page(&app, TARGET_NAME, get_warnings(alert_manager));
     Ok(ReplaceDirector::with_target(
         TECHNICAL,
@@ -116,17 +186,32 @@ pub fn plus_artistic(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
-    let art_penalties = state.write(|app_state| {
-        let position = get_position(&mut *app_state);
-        let scoresheet = get_scoresheet(&mut *app_state)?;
+    guard_penalty(&app)?;
+    let (art_penalties, change, sheet_id) = state.write(|app_state| {
+        app_state.tracked(|app_state| {
+            let position = get_position(&mut *app_state);
+            let previous = current_elimination(app_state);
+            let scoresheet = get_scoresheet(&mut *app_state)?;
 
-        scoresheet.art_penalties += 1;
-        alert_manager.toggle(
-            AlertType::ArtisticPenalty(scoresheet.art_penalties),
-            &position,
-        );
-        Ok(scoresheet.art_penalties)
+            scoresheet.art_penalties += 1;
+            alert_manager.toggle(
+                AlertType::ArtisticPenalty(scoresheet.art_penalties),
+                &position,
+            );
+            let art_penalties = scoresheet.art_penalties;
+            let sheet_id = scoresheet.id.ulid();
+            let change = update_elimination(app_state, previous, &alert_manager, &position);
+            Ok((art_penalties, change, sheet_id))
+        })
     })??;
+    broadcast_penalty(
+        &app,
+        sheet_id,
+        BroadcastPenaltyVariety::ArtisticPenalty,
+        art_penalties,
+    );
+    emit_status_change(&app, change);
+    emit_deductions(&app, &state);
     emit_page(&app, TARGET_NAME, get_warnings(alert_manager));
     Ok(ReplaceDirector::with_target(
         ARTISTIC,
@@ -139,28 +224,116 @@ pub fn sub_artistic(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
 ) -> ResponseDirector {
-    let art_penalties = state.write(|app_state| {
-        let position = get_position(&mut *app_state);
-        let scoresheet = get_scoresheet(&mut *app_state)?;
-        alert_manager.toggle(
-            AlertType::ArtisticPenalty(scoresheet.art_penalties),
-            &position,
-        );
-        scoresheet.errors = scoresheet.art_penalties.saturating_sub(1);
-        Ok(scoresheet.art_penalties)
+    guard_penalty(&app)?;
+    let (art_penalties, change, sheet_id) = state.write(|app_state| {
+        app_state.tracked(|app_state| {
+            let position = get_position(&mut *app_state);
+            let previous = current_elimination(app_state);
+            let scoresheet = get_scoresheet(&mut *app_state)?;
+            alert_manager.toggle(
+                AlertType::ArtisticPenalty(scoresheet.art_penalties),
+                &position,
+            );
+            scoresheet.art_penalties = scoresheet.art_penalties.saturating_sub(1);
+            let art_penalties = scoresheet.art_penalties;
+            let sheet_id = scoresheet.id.ulid();
+            let change = update_elimination(app_state, previous, &alert_manager, &position);
+            Ok((art_penalties, change, sheet_id))
+        })
     })??;
+    broadcast_penalty(
+        &app,
+        sheet_id,
+        BroadcastPenaltyVariety::ArtisticPenalty,
+        art_penalties,
+    );
+    emit_status_change(&app, change);
+    emit_deductions(&app, &state);
     emit_page(&app, TARGET_NAME, get_warnings(alert_manager));
     Ok(ReplaceDirector::with_target(
         ARTISTIC,
         artistic_row(true, art_penalties).render(),
     ))
 }
+/// The reason the current scoresheet is eliminated by its penalties, if any
+fn current_elimination(app_state: &ApplicationState) -> Option<String> {
+    let test = app_state.get_test()?;
+    app_state.scoresheet()?.elimination(test)
+}
+
+struct StatusChange {
+    sheet_id: ulid::Ulid,
+    status: StarterResult,
+}
+
+/// Eliminates the starter when a penalty count reaches an elimination in the
+/// test sheet, and reinstates them when the count which eliminated them is
+/// taken back off, such as when it was entered by mistake.
+fn update_elimination(
+    app_state: &mut ApplicationState,
+    previous: Option<String>,
+    alert_manager: &AlertManager,
+    position: &Position,
+) -> Option<StatusChange> {
+    app_state.check_edit(SheetEdit::Status).ok()?;
+    let current = current_elimination(app_state);
+    let starter = app_state.starter_mut()?;
+    let sheet_id = starter.scoresheets.first()?.id.ulid();
+    let status = match (current, previous) {
+        (Some(reason), _) if !matches!(starter.status, StarterResult::Eliminated(_)) => {
+            StarterResult::Eliminated(reason)
+        }
+        (None, Some(reason)) if starter.status == StarterResult::Eliminated(reason.clone()) => {
+            StarterResult::InProgress(0)
+        }
+        _ => return None,
+    };
+    let previous = std::mem::replace(&mut starter.status, status.clone());
+    if let StarterResult::Eliminated(_) = previous {
+        alert_manager.set(AlertType::Status(previous), position, false);
+    }
+    if let StarterResult::Eliminated(_) = status {
+        alert_manager.set(AlertType::Status(status.clone()), position, true);
+    }
+    Some(StatusChange { sheet_id, status })
+}
+fn emit_status_change(app: &tauri::AppHandle, change: Option<StatusChange>) {
+    if let Some(StatusChange { sheet_id, status }) = change {
+        emit_page(app, STATUS, status_selection(status.clone()));
+        broadcast_status(app, sheet_id, status);
+    }
+}
+fn emit_deductions(app: &tauri::AppHandle, state: &ManagedApplicationState) {
+    let report = state.read(|app_state| {
+        let test = app_state.get_test()?;
+        Some(app_state.scoresheet()?.deduction_report(test))
+    });
+    if let Ok(Some(report)) = report {
+        emit_page(app, DEDUCTIONS, deduction_report(report));
+    }
+}
 fn get_position(app_state: &mut ApplicationState) -> Position {
     app_state
         .get_jury_member()
         .map_or(C, |x| x.position.clone())
 }
+/// Refuses the penalty, showing why, while the sheet cannot be edited or the
+/// outbox is full. A penalty can eliminate the starter, so it is held to the
+/// status guard as well.
+fn guard_penalty(app: &tauri::AppHandle) -> Result<(), ReplaceDirector> {
+    guard_edit(app, SheetEdit::Penalties)
+        .and_then(|_| guard_edit(app, SheetEdit::Status))
+        .map_err(|_| ReplaceDirector::none())
+}
 fn get_scoresheet(app_state: &mut ApplicationState) -> Result<&mut Scoresheet, ReplaceDirector> {
+    app_state
+        .check_edit(SheetEdit::Penalties)
+        .map_err(|refusal| {
+            ReplaceDirector::with_target(
+                &PageLocation::MissingScoreAside,
+                edit_refused_dialog(&refusal).render(),
+            )
+        })?;
     app_state
         .scoresheet_mut()
         .ok_or_else(|| screen_error("Could not increase error due to poisoned lock"))
@@ -2,8 +2,12 @@ use hypertext::Renderable;
 use serde::Deserialize;
 
 use crate::{
-    commands::replace_director::{PageLocation, ReplaceDirector, ResponseDirector},
-    domain::starter::StarterResult,
+    commands::{
+        mark_comment::guard_edit,
+        replace_director::{emit_page, PageLocation, ReplaceDirector, ResponseDirector},
+        warnings::broadcast::broadcast_status,
+    },
+    domain::{edit_guard::SheetEdit, starter::StarterResult},
     state::ManagedApplicationState,
     templates::{error::screen_error, scoresheet::status_selection},
 };
@@ -11,17 +15,35 @@ use crate::{
 #[tauri::command]
 pub fn change_competitor_status(
     state: tauri::State<'_, ManagedApplicationState>,
+    handle: tauri::AppHandle,
     value: WrappedStatus,
 ) -> ResponseDirector {
     let WrappedStatus(value) = value;
-    let status = state.write(|app_state| {
-        let starter = app_state
-            .starter_mut()
-            .ok_or_else(|| screen_error("Could not increase error due to poisoned lock"))?;
-
-        starter.status = value.clone();
-        Ok(value)
+    if guard_edit(&handle, SheetEdit::Status).is_err() {
+        // the select has already moved, so put the confirmed status back
+        let current = state.read(|app_state| app_state.starter().map(|x| x.status.clone()))?;
+        if let Some(current) = current {
+            emit_page(
+                &handle,
+                &PageLocation::StatusSelector,
+                status_selection(current),
+            );
+        }
+        return Err(ReplaceDirector::none());
+    }
+    let (status, sheet_id) = state.write(|app_state| {
+        app_state.tracked(|app_state| -> Result<_, ReplaceDirector> {
+            let starter = app_state
+                .starter_mut()
+                .ok_or_else(|| screen_error("Could not increase error due to poisoned lock"))?;
+            starter.status = value.clone();
+            let sheet_id = starter.scoresheets.first().map(|x| x.id.ulid());
+            Ok((value, sheet_id))
+        })
     })??;
+    if let Some(sheet_id) = sheet_id {
+        broadcast_status(&handle, sheet_id, status.clone());
+    }
     Ok(ReplaceDirector::with_target(
         &PageLocation::StatusSelector,
         status_selection(status).render(),
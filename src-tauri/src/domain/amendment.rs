@@ -0,0 +1,152 @@
+use decimal::Decimal;
+
+use super::scoresheet::{ScoredMark, Scoresheet};
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AmendmentStatus {
+    /// Waiting on the chief judge or scorer
+    Pending,
+    /// The movements are open for the judge to mark again
+    Approved,
+    Rejected,
+    /// The judge has confirmed the new marks
+    Applied,
+}
+
+/// A request from the judge to correct movements after their marks
+/// have been confirmed, along with the marks as they were at the time.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Amendment {
+    pub id: ulid::Ulid,
+    pub movements: Vec<u16>,
+    pub reason: String,
+    pub requested_at: chrono::DateTime<chrono::Utc>,
+    pub status: AmendmentStatus,
+    #[serde(default)]
+    pub decided_by: Option<String>,
+    #[serde(default)]
+    pub original: Vec<ScoredMark>,
+}
+
+/// A mark which was changed after the judge gave it, either by an
+/// applied amendment or by the scorer when locking the sheet
+#[derive(Clone, Debug, PartialEq)]
+pub struct Correction {
+    pub number: u16,
+    pub original: Option<Decimal>,
+    pub corrected: Option<Decimal>,
+}
+
+impl Scoresheet {
+    pub fn request_amendment(&mut self, movements: Vec<u16>, reason: String) -> Amendment {
+        let original = self
+            .scores
+            .iter()
+            .filter(|x| movements.contains(&x.number))
+            .cloned()
+            .collect();
+        let amendment = Amendment {
+            id: ulid::Ulid::new(),
+            movements,
+            reason,
+            requested_at: chrono::Utc::now(),
+            status: AmendmentStatus::Pending,
+            decided_by: None,
+            original,
+        };
+        self.amendments.push(amendment.clone());
+        amendment
+    }
+
+    /// Records the decision on a pending amendment, returning the amendment
+    /// if it was waiting on one.
+    pub fn decide_amendment(
+        &mut self,
+        id: &ulid::Ulid,
+        approved: bool,
+        decided_by: Option<String>,
+    ) -> Option<&Amendment> {
+        let amendment = self
+            .amendments
+            .iter_mut()
+            .find(|x| x.id == *id && x.status == AmendmentStatus::Pending)?;
+        amendment.status = match approved {
+            true => AmendmentStatus::Approved,
+            false => AmendmentStatus::Rejected,
+        };
+        amendment.decided_by = decided_by;
+        Some(amendment)
+    }
+
+    /// The movements the judge may mark again on a locked sheet
+    pub fn amendable(&self) -> Vec<u16> {
+        self.amendments
+            .iter()
+            .filter(|x| x.status == AmendmentStatus::Approved)
+            .flat_map(|x| x.movements.iter().copied())
+            .collect()
+    }
+
+    pub fn has_pending_amendment(&self) -> bool {
+        self.amendments
+            .iter()
+            .any(|x| x.status == AmendmentStatus::Pending)
+    }
+
+    /// Marks the approved amendments as applied once the judge confirms the
+    /// sheet again, returning the scores of the movements which were amended.
+    pub fn apply_amendments(&mut self) -> Vec<ScoredMark> {
+        let mut movements = vec![];
+        for amendment in self
+            .amendments
+            .iter_mut()
+            .filter(|x| x.status == AmendmentStatus::Approved)
+        {
+            amendment.status = AmendmentStatus::Applied;
+            movements.extend(amendment.movements.iter().copied());
+        }
+        self.scores
+            .iter()
+            .filter(|x| movements.contains(&x.number))
+            .cloned()
+            .collect()
+    }
+
+    /// The marks that differ from those the judge first confirmed
+    pub fn corrections(&self) -> Vec<Correction> {
+        let mut corrections: Vec<Correction> = vec![];
+        for amendment in self
+            .amendments
+            .iter()
+            .filter(|x| x.status == AmendmentStatus::Applied)
+        {
+            for number in amendment.movements.iter() {
+                let original = amendment
+                    .original
+                    .iter()
+                    .find(|x| x.number == *number)
+                    .and_then(|x| x.mark);
+                match corrections.iter_mut().find(|x| x.number == *number) {
+                    // an earlier amendment already holds the first mark given
+                    Some(_) => (),
+                    None => corrections.push(Correction {
+                        number: *number,
+                        original,
+                        corrected: None,
+                    }),
+                }
+            }
+        }
+        corrections.retain_mut(|correction| {
+            correction.corrected = self
+                .scores
+                .iter()
+                .find(|x| x.number == correction.number)
+                .and_then(|x| x.mark);
+            correction.corrected != correction.original
+        });
+        corrections
+    }
+}
@@ -1,6 +1,11 @@
 use super::{
-    dressage_test::DressageTest, ground_jury_member::GroundJuryMember, position::Position,
-    starter::Starter, SurrealId,
+    dressage_test::{DressageTest, Precision, TestDiagnostic},
+    ground_jury_member::GroundJuryMember,
+    position::Position,
+    ranking::TieBreak,
+    remark_policy::RemarkPolicy,
+    starter::Starter,
+    SurrealId,
 };
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
@@ -13,6 +18,12 @@ pub struct Competition {
     pub tests: Vec<DressageTest>,
     pub jury: Vec<GroundJuryMember>,
     pub starters: Vec<Starter>,
+    #[serde(default)]
+    pub remark_policy: RemarkPolicy,
+    /// How starters on the same score are separated, when the competition
+    /// does not leave it to the type of its test
+    #[serde(default)]
+    pub tie_break: Option<TieBreak>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
@@ -35,6 +46,36 @@ impl Competition {
     pub fn get_position(&self) -> Option<Position> {
         self.jury.first().map(|j| &j.position).cloned()
     }
+    /// Validates every test sheet of the competition, including the tests
+    /// given to individual members of the jury.
+    pub fn diagnostics(&self) -> Vec<TestDiagnostic> {
+        self.tests
+            .iter()
+            .chain(self.jury.iter().filter_map(|j| j.test.as_ref()))
+            .flat_map(|test| test.validate())
+            .collect()
+    }
+    /// The precision of the competition's first test, for when there is no
+    /// scoresheet to tell which test a starter is marked on
+    pub fn precision(&self) -> Precision {
+        self.tests.first().map(|x| x.precision).unwrap_or_default()
+    }
+    /// Adds or updates starters sent by the server, keeping the start list
+    /// in running order. Returns the changes made to starters already listed.
+    pub fn upsert_starters(&mut self, starters: Vec<Starter>) -> Vec<(SurrealId, Vec<String>)> {
+        let mut changed = vec![];
+        for incoming in starters.into_iter() {
+            match self.starters.iter_mut().find(|x| x.id == incoming.id) {
+                Some(starter) => {
+                    let id = starter.id.clone();
+                    changed.push((id, starter.merge(incoming)));
+                }
+                None => self.starters.push(incoming),
+            }
+        }
+        self.starters.sort_by_key(|x| x.index);
+        changed
+    }
     pub fn get_test<'a>(&'a self, judge: &'a GroundJuryMember) -> &'a DressageTest {
         if self.tests.len() > 1 {
             if let Some(test) = &judge.test {
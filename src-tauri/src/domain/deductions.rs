@@ -0,0 +1,142 @@
+use decimal::{dec, Decimal};
+
+use super::dressage_test::{DressageTest, Precision};
+use super::penalties::{Penalties, Penalty, PenaltyType};
+use super::scoresheet::Scoresheet;
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PenaltySource {
+    ErrorsOfCourse,
+    TechnicalPenalties,
+    ArtisticPenalties,
+}
+impl std::fmt::Display for PenaltySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::ErrorsOfCourse => "Error of course",
+                Self::TechnicalPenalties => "Technical penalty",
+                Self::ArtisticPenalties => "Artistic penalty",
+            }
+        )
+    }
+}
+
+/// A single penalty which has been applied to a scoresheet
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppliedPenalty {
+    pub source: PenaltySource,
+    /// Which occurrence this is, starting from 1 for the first error
+    pub count: u8,
+    /// The entry of the test sheet's penalty schedule which was applied
+    pub index: u8,
+    pub ty: PenaltyType,
+    /// The effect on the final score in percentage terms, kept unrounded so
+    /// that the penalties add up exactly and are only rounded when shown
+    pub percentage: Decimal,
+    /// How the test publishes percentages, for showing the effect
+    pub precision: Precision,
+}
+impl std::fmt::Display for AppliedPenalty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let effect = self.precision.format(self.percentage);
+        match self.ty {
+            PenaltyType::Points(p) => {
+                write!(f, "{} {} ({p} pts): -{effect}%", self.source, self.count)
+            }
+            PenaltyType::Percentage(p) => {
+                write!(f, "{} {} ({p}%): -{effect}%", self.source, self.count)
+            }
+            PenaltyType::Elimination => write!(f, "{} {}: Eliminated", self.source, self.count),
+        }
+    }
+}
+
+/// Every penalty applied to a scoresheet, so the judge can explain exactly
+/// where a deduction came from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeductionReport {
+    pub penalties: Vec<AppliedPenalty>,
+    pub precision: Precision,
+}
+impl DeductionReport {
+    /// The deduction from the score, which is the one taken off the trend
+    pub fn total(&self) -> Decimal {
+        self.penalties
+            .iter()
+            .fold(dec!(0.000), |sum, pen| sum + pen.percentage)
+    }
+    /// The deduction from a single list of penalties
+    pub fn source_total(&self, source: PenaltySource) -> Decimal {
+        self.penalties
+            .iter()
+            .filter(|pen| pen.source == source)
+            .fold(dec!(0.000), |sum, pen| sum + pen.percentage)
+    }
+    pub fn elimination(&self) -> Option<&AppliedPenalty> {
+        self.penalties
+            .iter()
+            .find(|pen| pen.ty == PenaltyType::Elimination)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.penalties.is_empty()
+    }
+}
+
+impl Scoresheet {
+    pub fn deduction_report(&self, test: &DressageTest) -> DeductionReport {
+        let point_value = dec!(100.000)
+            .safe_divide(test.total_marks(), 6)
+            .unwrap_or_default();
+        let mut penalties = vec![];
+        for (source, count, schedule) in [
+            (
+                PenaltySource::ErrorsOfCourse,
+                self.errors,
+                &test.errors_of_course,
+            ),
+            (
+                PenaltySource::TechnicalPenalties,
+                self.tech_penalties,
+                &test.technical_penalties,
+            ),
+            (
+                PenaltySource::ArtisticPenalties,
+                self.art_penalties,
+                &test.artistic_penalties,
+            ),
+        ] {
+            for (count, pen) in scheduled(count, schedule) {
+                penalties.push(AppliedPenalty {
+                    source,
+                    count,
+                    index: pen.idx,
+                    ty: pen.ty.clone(),
+                    percentage: match pen.ty {
+                        PenaltyType::Points(num) => num * point_value,
+                        PenaltyType::Percentage(num) => num,
+                        PenaltyType::Elimination => dec!(0.000),
+                    },
+                    precision: test.precision,
+                });
+            }
+        }
+        DeductionReport {
+            penalties,
+            precision: test.precision,
+        }
+    }
+}
+
+/// Pairs each occurrence up to the count with its entry in the penalty
+/// schedule, where counts past the end of the schedule repeat the final entry.
+fn scheduled(count: u8, penalties: &Penalties) -> impl Iterator<Item = (u8, &Penalty)> {
+    (0..count).map_while(move |i| {
+        penalties
+            .get(usize::min(i as usize, penalties.len().saturating_sub(1)))
+            .map(|pen| (i + 1, pen))
+    })
+}
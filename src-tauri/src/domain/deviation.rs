@@ -0,0 +1,119 @@
+use decimal::{dec, Decimal, RoundingMode};
+
+use super::competition::Competition;
+use super::position::Position;
+use super::starter::Starter;
+
+/// How many marks a judge may be away from the panel median on a movement
+fn movement_tolerance() -> Decimal {
+    dec!(2.0)
+}
+/// How many percent a judge's score may be away from the panel median
+fn accepted_spread() -> Decimal {
+    dec!(5.0)
+}
+
+/// A judge whose marks are far enough from the rest of the panel that the
+/// chief judge may want to call a discussion before results are published.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Deviation {
+    Movement {
+        position: Position,
+        movement: u8,
+        mark: Decimal,
+        median: Decimal,
+    },
+    Score {
+        position: Position,
+        score: Decimal,
+        median: Decimal,
+    },
+}
+
+impl Starter {
+    /// Compares the scoresheets of the actual jury against each other. Shadow,
+    /// Observer and Removed members are ignored, as they don't change the result.
+    /// Two judges who disagree would deviate from each other equally, so it
+    /// takes a panel of at least three to tell which one is out of line.
+    pub fn deviations(&self, competition: &Competition) -> Vec<Deviation> {
+        let sheets = self
+            .jury_sheets(competition)
+            .filter(|(_, member, _)| member.authority.is_actual_jury())
+            .collect::<Vec<_>>();
+        let mut deviations = vec![];
+        if sheets.len() < 3 {
+            return deviations;
+        }
+
+        let Some((_, _, test)) = sheets.first() else {
+            return deviations;
+        };
+        for movement in test.movements.iter() {
+            let marks = sheets
+                .iter()
+                .filter_map(|(sheet, member, _)| {
+                    sheet
+                        .scores
+                        .iter()
+                        .find(|x| x.number == movement.number as u16)
+                        .and_then(|x| x.mark)
+                        .map(|mark| (member.position.clone(), mark))
+                })
+                .collect::<Vec<_>>();
+            let Some(median) = median(marks.iter().map(|(_, mark)| *mark).collect()) else {
+                continue;
+            };
+            for (position, mark) in marks {
+                if difference(mark, median) > movement_tolerance() {
+                    deviations.push(Deviation::Movement {
+                        position,
+                        movement: movement.number,
+                        mark,
+                        median,
+                    });
+                }
+            }
+        }
+
+        let panel = self.panel(competition);
+        let scores = panel
+            .contributions
+            .into_iter()
+            .filter(|x| x.counted)
+            .filter_map(|x| x.score.map(|score| (x.position, score)))
+            .collect::<Vec<_>>();
+        if let Some(median) = median(scores.iter().map(|(_, score)| *score).collect()) {
+            for (position, score) in scores {
+                if difference(score, median) > accepted_spread() {
+                    deviations.push(Deviation::Score {
+                        position,
+                        score,
+                        median,
+                    });
+                }
+            }
+        }
+        deviations
+    }
+}
+
+/// Needs at least three values, with two both are as far from the median
+fn median(mut values: Vec<Decimal>) -> Option<Decimal> {
+    if values.len() < 3 {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let middle = values.len() / 2;
+    match values.len() % 2 {
+        0 => Decimal::average(&values[middle - 1..=middle], 3, RoundingMode::HalfUp),
+        _ => values.get(middle).copied(),
+    }
+}
+
+fn difference(a: Decimal, b: Decimal) -> Decimal {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use decimal::{dec, Decimal};
+
+use super::{DressageTest, Exercise};
+use crate::domain::scoresheet::{step_bounds, Scoresheet};
+
+/// How a declared difficulty is put together in the choreography
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum CombinationCategory {
+    #[serde(rename = "S")]
+    Single,
+    #[serde(rename = "T")]
+    Transition,
+    #[serde(rename = "C")]
+    Combination,
+}
+
+/// Whether a difficulty must be shown or is one the rider may choose to show
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub enum DifficultyOption {
+    #[serde(rename = "C")]
+    Compulsory,
+    #[serde(rename = "O")]
+    Optional,
+    #[serde(rename = "B")]
+    Bonus,
+}
+
+/// A declared difficulty which the judge has seen executed
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShownDifficulty {
+    /// The movement slot of the choreography the difficulty was declared in
+    pub slot: u8,
+    pub difficulty: Decimal,
+    /// How well it was executed, as a fraction of the movement maximum
+    pub quality: Decimal,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DifficultyEvaluation {
+    pub declared: usize,
+    pub shown: Vec<ShownDifficulty>,
+    /// Declared slots which have not been shown yet
+    pub missing: Vec<u8>,
+    /// Rows which were executed again after their slot was already shown,
+    /// these do not count towards the degree of difficulty
+    pub repeats: Vec<u8>,
+    /// The artistic movement for the degree of difficulty and the mark
+    /// suggested for it
+    pub suggestion: Option<(u8, Decimal)>,
+}
+
+impl Exercise {
+    /// The choreography slot this row is an attempt at
+    pub fn slot(&self) -> u8 {
+        self.mapping_index.unwrap_or(self.number)
+    }
+    fn is_declared(&self) -> bool {
+        self.difficulty.is_some() && !self.repeat && self.mapping_index.is_none()
+    }
+}
+
+impl DressageTest {
+    /// The artistic movement the degree of difficulty is marked under
+    pub fn difficulty_movement(&self) -> Option<&Exercise> {
+        self.movements.iter().find(|x| x.degree_of_difficulty)
+    }
+}
+
+impl Scoresheet {
+    /// Maps the marked rows onto the declared difficulties of a freestyle.
+    /// A slot counts once, using the first row which shows it, rows marked as
+    /// a repeat or shown after their slot was already counted are flagged.
+    pub fn evaluate_difficulty(&self, test: &DressageTest) -> DifficultyEvaluation {
+        let mut evaluation = DifficultyEvaluation::default();
+        let mut qualities: HashMap<u8, Decimal> = HashMap::new();
+        for movement in test.movements.iter() {
+            let Some(mark) = self
+                .scores
+                .iter()
+                .find(|x| x.number == movement.number as u16)
+                .and_then(|x| x.mark)
+            else {
+                continue;
+            };
+            if mark <= movement.min {
+                continue;
+            }
+            let slot = movement.slot();
+            if movement.repeat || qualities.contains_key(&slot) {
+                evaluation.repeats.push(movement.number);
+                continue;
+            }
+            let quality = mark.safe_divide(movement.max, 6).unwrap_or_default();
+            qualities.insert(slot, quality);
+        }
+
+        let mut possible = dec!(0.0);
+        let mut achieved = dec!(0.0);
+        for movement in test.movements.iter().filter(|x| x.is_declared()) {
+            let Some(difficulty) = movement.difficulty else {
+                continue;
+            };
+            evaluation.declared += 1;
+            possible += difficulty;
+            match qualities.get(&movement.number) {
+                Some(quality) => {
+                    achieved += difficulty * *quality;
+                    evaluation.shown.push(ShownDifficulty {
+                        slot: movement.number,
+                        difficulty,
+                        quality: *quality,
+                    });
+                }
+                None => evaluation.missing.push(movement.number),
+            }
+        }
+
+        if let (Some(movement), Ok(ratio)) = (
+            test.difficulty_movement(),
+            achieved.safe_divide(possible, 6),
+        ) {
+            let value = ratio * movement.max;
+            let (down, up) = step_bounds(value, movement.step);
+            let mark = if value - down < up - value { down } else { up };
+            let mark = if mark < movement.min {
+                movement.min
+            } else {
+                mark
+            };
+            evaluation.suggestion = Some((movement.number, mark));
+        }
+        evaluation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal::{dec, Decimal};
+
+    use crate::domain::dressage_test::{DressageTest, Exercise, TestSheetType};
+    use crate::domain::scoresheet::{ScoredMark, Scoresheet};
+    use crate::domain::SurrealId;
+
+    fn movement(value: serde_json::Value) -> Exercise {
+        serde_json::from_value(value).expect("A valid movement")
+    }
+
+    /// Two declared difficulties, an attempt at the first one again, a row
+    /// marked as a repeat and the degree of difficulty
+    fn freestyle() -> DressageTest {
+        DressageTest {
+            movements: vec![
+                movement(serde_json::json!({"nr": 1, "df": 0.6})),
+                movement(serde_json::json!({"nr": 2, "df": 0.4})),
+                movement(serde_json::json!({"nr": 3, "mi": 1})),
+                movement(serde_json::json!({"nr": 4, "df": 0.4, "rp": true})),
+                movement(serde_json::json!({"nr": 5, "ct": "A", "dd": true})),
+            ],
+            test_type: TestSheetType::Freestyle,
+            ..Default::default()
+        }
+    }
+
+    fn sheet(marks: &[(u16, Decimal)]) -> Scoresheet {
+        Scoresheet {
+            id: SurrealId::make("scoresheet", "difficulty"),
+            score: None,
+            rank: None,
+            errors: 0,
+            tech_penalties: 0,
+            art_penalties: 0,
+            scores: marks
+                .iter()
+                .map(|(number, mark)| ScoredMark {
+                    mark: Some(*mark),
+                    ..ScoredMark::new(*number)
+                })
+                .collect(),
+            summary: None,
+            notes: None,
+            warning_manager: Default::default(),
+            locked: false,
+            test: None,
+            ground_jury_member: None,
+            history: Default::default(),
+            amendments: vec![],
+        }
+    }
+
+    #[test]
+    fn repeats_do_not_count() {
+        let sheet = sheet(&[
+            (1, dec!(8.0)),
+            (2, dec!(7.0)),
+            (3, dec!(10.0)),
+            (4, dec!(10.0)),
+        ]);
+        let evaluation = sheet.evaluate_difficulty(&freestyle());
+        assert_eq!(evaluation.repeats, vec![3, 4]);
+        assert_eq!(evaluation.declared, 2);
+        assert_eq!(evaluation.shown.len(), 2);
+        assert_eq!(evaluation.shown[0].slot, 1);
+        assert_eq!(evaluation.shown[0].quality, dec!(0.8));
+    }
+
+    #[test]
+    fn missing_slots_are_listed() {
+        let evaluation = sheet(&[(1, dec!(8.0))]).evaluate_difficulty(&freestyle());
+        assert_eq!(evaluation.missing, vec![2]);
+        assert_eq!(evaluation.shown.len(), 1);
+    }
+
+    #[test]
+    fn the_suggestion_snaps_to_the_movement_step() {
+        // 0.6 at 80% and 0.4 at 70% is 76% of the difficulty declared
+        let sheet = sheet(&[(1, dec!(8.0)), (2, dec!(7.0))]);
+        let evaluation = sheet.evaluate_difficulty(&freestyle());
+        assert_eq!(evaluation.suggestion, Some((5, dec!(7.5))));
+    }
+
+    #[test]
+    fn only_the_marked_movement_is_given_a_suggestion() {
+        let mut test = freestyle();
+        test.movements.retain(|x| !x.degree_of_difficulty);
+        let sheet = sheet(&[(1, dec!(8.0)), (2, dec!(7.0))]);
+        assert_eq!(sheet.evaluate_difficulty(&test).suggestion, None);
+    }
+}
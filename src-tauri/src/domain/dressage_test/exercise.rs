@@ -57,20 +57,30 @@ pub struct Exercise {
 
     #[serde(rename = "di", default, skip_serializing_if = "Option::is_none")]
     pub directive_ideas: Option<Vec<String>>,
-    // #[serde(rename = "df", default, skip_serializing_if = "Option::is_none")]
-    // pub difficulty: Option<f32>,
+    #[serde(
+        rename = "df",
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "decimal::parsing::deserialize_opt_from_f64",
+        serialize_with = "decimal::parsing::serialize_opt_as_f64"
+    )]
+    pub difficulty: Option<Decimal>,
+
+    #[serde(rename = "rp", default, skip_serializing_if = "std::ops::Not::not")]
+    pub repeat: bool,
 
-    // #[serde(rename = "rp", default, skip_serializing_if = "std::ops::Not::not")]
-    // pub repeat: bool,
+    #[serde(rename = "cm", default, skip_serializing_if = "Option::is_none")]
+    pub combination: Option<super::CombinationCategory>,
 
-    // #[serde(rename = "cm", default, skip_serializing_if = "Option::is_none")]
-    // pub combination: Option<CombinationCategory>,
+    #[serde(rename = "do", default, skip_serializing_if = "Option::is_none")]
+    pub option: Option<super::DifficultyOption>,
 
-    // #[serde(rename = "do", default, skip_serializing_if = "Option::is_none")]
-    // pub option: Option<DifficultyOption>,
+    #[serde(rename = "mi", default, skip_serializing_if = "Option::is_none")]
+    pub mapping_index: Option<u8>,
 
-    // #[serde(rename = "mi", default, skip_serializing_if = "Option::is_none")]
-    // pub mapping_index: Option<u8>,
+    /// The artistic movement the degree of difficulty is marked under
+    #[serde(rename = "dd", default, skip_serializing_if = "std::ops::Not::not")]
+    pub degree_of_difficulty: bool,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
@@ -0,0 +1,231 @@
+use std::str::FromStr;
+
+use decimal::{dec, Decimal};
+
+use super::Exercise;
+
+/// What the judge has typed so far, measured against the movement
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParsedMark {
+    /// Not a number at all, so the input should be cleared
+    Unparseable,
+    /// A number, but not one which can be given for this movement
+    OutOfBounds,
+    /// A valid mark, but the judge could still be typing a different one,
+    /// eg. `7` could become `7.5`
+    Incomplete(Decimal),
+    /// A valid mark which no further keystroke could change
+    Complete(Decimal),
+}
+
+/// Parses the keystrokes of the mark and attempt inputs against the min,
+/// max and step of a movement. A number above the maximum is shifted down
+/// a decimal place until it fits, so `75` can be typed for `7.5`.
+pub struct MarkParser<'a> {
+    movement: &'a Exercise,
+}
+
+impl Exercise {
+    pub fn mark_parser(&self) -> MarkParser<'_> {
+        MarkParser { movement: self }
+    }
+}
+
+impl MarkParser<'_> {
+    pub fn parse(&self, value: &str) -> ParsedMark {
+        // A trailing decimal point is waiting for the decimal place to be typed
+        if let Some(whole) = value.strip_suffix('.') {
+            if whole.contains('.') {
+                return ParsedMark::Unparseable;
+            }
+            if self.movement.step.scale() == 0 {
+                return ParsedMark::OutOfBounds;
+            }
+            return match Decimal::from_str(whole) {
+                Ok(raw) if raw > self.movement.max => ParsedMark::OutOfBounds,
+                Ok(_) => match self.value(whole) {
+                    Ok(mark) => ParsedMark::Incomplete(mark),
+                    Err(outcome) => outcome,
+                },
+                Err(_) => ParsedMark::Unparseable,
+            };
+        }
+        match self.value(value) {
+            Ok(mark) if self.can_change(value, mark) => ParsedMark::Incomplete(mark),
+            Ok(mark) => ParsedMark::Complete(mark),
+            Err(outcome) => outcome,
+        }
+    }
+
+    fn value(&self, value: &str) -> Result<Decimal, ParsedMark> {
+        let Exercise { min, max, step, .. } = *self.movement;
+        if value.is_empty()
+            || !value
+                .bytes()
+                .all(|c| matches!(c, b'.' | b'-' | b'0'..=b'9'))
+        {
+            return Err(ParsedMark::Unparseable);
+        }
+        let mut mark = Decimal::from_str(value).map_err(|_| ParsedMark::Unparseable)?;
+        if mark < min || mark.scale() > step.scale() || step == dec!(0.0) {
+            return Err(ParsedMark::OutOfBounds);
+        }
+        while mark > max {
+            mark = mark
+                .safe_divide(dec!(10.0), 3)
+                .map_err(|_| ParsedMark::Unparseable)?;
+        }
+        if mark < min || mark % step != dec!(0.0) {
+            return Err(ParsedMark::OutOfBounds);
+        }
+        Ok(mark.to_precision(step.scale()))
+    }
+
+    /// Whether typing another digit would give a different valid mark
+    fn can_change(&self, value: &str, mark: Decimal) -> bool {
+        (0..=9)
+            .any(|digit| matches!(self.value(&format!("{value}{digit}")), Ok(next) if next != mark))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use decimal::{dec, Decimal};
+
+    use super::ParsedMark::*;
+    use crate::domain::dressage_test::Exercise;
+
+    fn movement(max: Decimal, step: Decimal) -> Exercise {
+        let mut movement: Exercise =
+            serde_json::from_value(serde_json::json!({ "nr": 1 })).expect("A valid movement");
+        movement.min = dec!(0.0);
+        movement.max = max;
+        movement.step = step;
+        movement
+    }
+
+    fn number(value: &str) -> Decimal {
+        Decimal::from_str(value).expect("A number")
+    }
+
+    /// Every mark from zero to the maximum in tenths, as the judge types it
+    fn tenths(max: u32) -> impl Iterator<Item = (u32, String)> {
+        (0..=max * 10).map(|x| match x % 10 {
+            0 => (x, format!("{}", x / 10)),
+            tenth => (x, format!("{}.{tenth}", x / 10)),
+        })
+    }
+
+    /// Checks each keystroke on the way to every mark on the grid
+    fn assert_grid(max: u32, step_in_tenths: u32) {
+        let step = number(&format!("0.{step_in_tenths}"));
+        let movement = movement(number(&format!("{max}.0")), step);
+        let parser = movement.mark_parser();
+        let parse = |value: &str| parser.parse(value);
+
+        for (x, typed) in tenths(max).filter(|(x, _)| x % step_in_tenths == 0) {
+            let mark = number(&typed);
+            let whole = (x / 10).to_string();
+            match x % 10 {
+                // a whole mark could still have a decimal place added to
+                // it, unless it is the maximum
+                0 if x == max * 10 => assert_eq!(parse(&typed), Complete(mark), "{typed}"),
+                0 => assert_eq!(parse(&typed), Incomplete(mark), "{typed}"),
+                tenth => {
+                    let whole_mark = Incomplete(number(&whole));
+                    assert_eq!(parse(&whole), whole_mark, "{whole} of {typed}");
+                    assert_eq!(
+                        parse(&format!("{whole}.")),
+                        whole_mark,
+                        "{whole}. of {typed}"
+                    );
+                    assert_eq!(parse(&typed), Complete(mark), "{typed}");
+                    // typed without the decimal point, eg. 75 for 7.5
+                    if x >= 10 {
+                        let shifted = format!("{whole}{tenth}");
+                        assert_eq!(parse(&shifted), Complete(mark), "{shifted}");
+                    }
+                }
+            }
+            // the first digit of a two digit mark
+            if typed.len() == 2 && !typed.contains('.') {
+                assert_eq!(
+                    parse(&typed[..1]),
+                    Incomplete(number(&typed[..1])),
+                    "{typed}"
+                );
+            }
+        }
+
+        // marks off the grid, or above the maximum
+        for (_, typed) in tenths(max + 1).filter(|(x, _)| x % step_in_tenths != 0) {
+            assert_eq!(parse(&typed), OutOfBounds, "{typed}");
+        }
+        for typed in [format!("{max}.5"), "-1".to_string()] {
+            assert_eq!(parse(&typed), OutOfBounds, "{typed}");
+        }
+    }
+
+    #[test]
+    fn half_marks_out_of_ten() {
+        assert_grid(10, 5);
+    }
+
+    #[test]
+    fn tenths_out_of_ten() {
+        assert_grid(10, 1);
+    }
+
+    #[test]
+    fn half_marks_out_of_five() {
+        assert_grid(5, 5);
+    }
+
+    #[test]
+    fn shifts_a_number_above_the_maximum() {
+        let movement = movement(dec!(10.0), dec!(0.5));
+        let parser = movement.mark_parser();
+        assert_eq!(parser.parse("75"), Complete(dec!(7.5)));
+        assert_eq!(parser.parse("7"), Incomplete(dec!(7.0)));
+        assert_eq!(parser.parse("10"), Complete(dec!(10.0)));
+        assert_eq!(parser.parse("1"), Incomplete(dec!(1.0)));
+        assert_eq!(parser.parse("100"), Complete(dec!(10.0)));
+        assert_eq!(parser.parse("73"), OutOfBounds);
+        assert_eq!(parser.parse("105"), OutOfBounds);
+    }
+
+    #[test]
+    fn waits_on_a_trailing_decimal_point() {
+        let movement = movement(dec!(10.0), dec!(0.5));
+        let parser = movement.mark_parser();
+        assert_eq!(parser.parse("7."), Incomplete(dec!(7.0)));
+        assert_eq!(parser.parse("0."), Incomplete(dec!(0.0)));
+        assert_eq!(parser.parse("10."), Incomplete(dec!(10.0)));
+        assert_eq!(parser.parse("75."), OutOfBounds);
+        assert_eq!(parser.parse("7.5."), Unparseable);
+        assert_eq!(parser.parse("."), Unparseable);
+
+        // whole marks only have no decimal place to wait for
+        let whole_marks = self::movement(dec!(10.0), number("1"));
+        assert_eq!(whole_marks.mark_parser().parse("7."), OutOfBounds);
+    }
+
+    #[test]
+    fn rejects_what_is_not_a_number() {
+        let movement = movement(dec!(10.0), dec!(0.5));
+        let parser = movement.mark_parser();
+        for typed in ["", "a", "-", "7,5", "7a", "7.5.0", "1e1"] {
+            assert_eq!(parser.parse(typed), Unparseable, "{typed}");
+        }
+    }
+
+    #[test]
+    fn rejects_more_decimal_places_than_the_step() {
+        let movement = movement(dec!(10.0), dec!(0.5));
+        let parser = movement.mark_parser();
+        assert_eq!(parser.parse("7.50"), OutOfBounds);
+        assert_eq!(parser.parse("7.55"), OutOfBounds);
+    }
+}
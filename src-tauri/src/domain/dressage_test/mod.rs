@@ -1,8 +1,18 @@
+mod difficulty;
 mod exercise;
+mod mark_parser;
 mod movement_category;
+mod precision;
 mod testsheet;
+mod validation;
 
+pub use difficulty::{
+    CombinationCategory, DifficultyEvaluation, DifficultyOption, ShownDifficulty,
+};
 pub use exercise::coefficient_default;
 pub use exercise::Exercise;
-pub use movement_category::MovementCategory;
+pub use mark_parser::{MarkParser, ParsedMark};
+pub use movement_category::{Counting, MovementCategory};
+pub use precision::{Precision, Rounding};
 pub use testsheet::{DressageTest, TestSheetType};
+pub use validation::TestDiagnostic;
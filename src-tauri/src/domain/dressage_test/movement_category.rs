@@ -8,11 +8,23 @@ pub enum MovementCategory {
     #[serde(rename = "C")]
     Collective,
     #[serde(rename = "J")]
-    Joker, // TODO: maybe should contain mapping index??
+    Joker, // optional, only counted once marked. TODO: maybe should contain mapping index??
     #[serde(rename = "P")]
     Particle, // never counted regardless of coefficient
     #[serde(rename = "E")]
-    Acceptable, // combinations and transitions
+    Acceptable, // combinations and transitions, counted as a technical movement
+}
+
+/// How a movement of a category contributes to the maximum marks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Counting {
+    /// Always part of the maximum, so an unmarked movement scores nothing
+    /// once the test is complete
+    Always,
+    /// Only part of the maximum once the judge has given it a mark
+    WhenMarked,
+    /// Never part of the score or the maximum
+    Never,
 }
 impl MovementCategory {
     pub fn is_technical(&self) -> bool {
@@ -21,6 +33,18 @@ impl MovementCategory {
     pub fn has_letters(&self) -> bool {
         Self::Technical == *self || Self::Particle == *self
     }
+    pub fn counting(&self) -> Counting {
+        match self {
+            Self::Technical | Self::Artistic | Self::Collective | Self::Acceptable => {
+                Counting::Always
+            }
+            Self::Joker => Counting::WhenMarked,
+            Self::Particle => Counting::Never,
+        }
+    }
+    pub fn is_artistic(&self) -> bool {
+        Self::Artistic == *self || Self::Collective == *self
+    }
     pub fn has_attempts(&self) -> bool {
         Self::Technical == *self
     }
@@ -43,3 +67,26 @@ impl std::fmt::Display for MovementCategory {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::{Counting, MovementCategory};
+
+    #[test]
+    fn categories_count_according_to_their_role() {
+        use MovementCategory::*;
+        assert_eq!(Technical.counting(), Counting::Always);
+        assert_eq!(Artistic.counting(), Counting::Always);
+        assert_eq!(Collective.counting(), Counting::Always);
+        assert_eq!(Acceptable.counting(), Counting::Always);
+        assert_eq!(Joker.counting(), Counting::WhenMarked);
+        assert_eq!(Particle.counting(), Counting::Never);
+    }
+
+    #[test]
+    fn acceptables_are_scored_as_technical_and_collectives_as_artistic() {
+        assert!(!MovementCategory::Acceptable.is_artistic());
+        assert!(MovementCategory::Collective.is_artistic());
+        assert!(MovementCategory::Artistic.is_artistic());
+        assert!(!MovementCategory::Technical.is_artistic());
+    }
+}
@@ -0,0 +1,47 @@
+use decimal::{Decimal, RoundingMode};
+
+/// How percentages are rounded and how many decimal places are published,
+/// as some federations publish at 2 decimals or truncate instead of rounding.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Precision {
+    #[serde(default = "default_places")]
+    pub places: u8,
+    #[serde(default)]
+    pub rounding: Rounding,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Rounding {
+    #[default]
+    HalfUp,
+    Truncate,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self {
+            places: default_places(),
+            rounding: Rounding::default(),
+        }
+    }
+}
+
+impl Precision {
+    pub fn round(&self, value: Decimal) -> Decimal {
+        let mode = match self.rounding {
+            Rounding::HalfUp => RoundingMode::HalfUp,
+            Rounding::Truncate => RoundingMode::Down,
+        };
+        value.round_ext(self.places, mode)
+    }
+    /// Rounds the value and pads it out to the number of places
+    pub fn format(&self, value: Decimal) -> String {
+        self.round(value).to_precision(self.places).to_string()
+    }
+}
+
+const fn default_places() -> u8 {
+    3
+}
@@ -2,7 +2,7 @@ use decimal::{dec, Decimal};
 
 use crate::domain::{penalties::Penalties, SurrealId};
 
-use super::Exercise;
+use super::{Counting, Exercise, Precision};
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +18,8 @@ pub struct DressageTest {
     pub countdowns: [u8; 2],
     #[serde(default = "default_test_length")]
     pub length_in_seconds: u16,
+    #[serde(default)]
+    pub precision: Precision,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -36,10 +38,15 @@ impl crate::traits::Entity for DressageTest {
     }
 }
 impl DressageTest {
+    /// The maximum marks of the test, which leaves out any movements
+    /// that are optional or never counted.
     pub fn total_marks(&self) -> Decimal {
-        self.movements.iter().fold(dec!(0.0), |sum, movement| {
-            sum + (movement.max * movement.coefficient)
-        })
+        self.movements
+            .iter()
+            .filter(|movement| movement.category.counting() == Counting::Always)
+            .fold(dec!(0.0), |sum, movement| {
+                sum + (movement.max * movement.coefficient)
+            })
     }
 }
 
@@ -83,6 +90,7 @@ impl Default for DressageTest {
             test_type: TestSheetType::Normal,
             countdowns: default_countdowns(),
             length_in_seconds: default_test_length(),
+            precision: Precision::default(),
         }
     }
 }
@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+
+use decimal::dec;
+
+use super::{DressageTest, TestSheetType};
+
+/// Problems with a test sheet. Fatal ones would stop it from being scored,
+/// the rest are only worth pointing out to the judge.
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum TestDiagnostic {
+    #[error("{test} has no movements")]
+    NoMovements { test: String },
+    #[error("{test} has a maximum of zero marks")]
+    ZeroTotalMarks { test: String },
+    #[error("{test} has no penalties for errors of course")]
+    NoErrorsOfCourse { test: String },
+    #[error("Movement {movement} of {test} appears more than once")]
+    DuplicateMovement { test: String, movement: u8 },
+    #[error("Movement {movement} of {test} has a step of zero")]
+    ZeroStep { test: String, movement: u8 },
+    #[error("Movement {movement} of {test} has a minimum above its maximum")]
+    MinAboveMax { test: String, movement: u8 },
+    #[error("Movement {movement} of {test} has a maximum which is not a multiple of its step")]
+    MaxOffStep { test: String, movement: u8 },
+}
+
+impl TestDiagnostic {
+    /// Whether the test cannot be marked at all, rather than just looking odd
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            Self::NoMovements { .. }
+                | Self::ZeroTotalMarks { .. }
+                | Self::NoErrorsOfCourse { .. }
+                | Self::ZeroStep { .. }
+                | Self::MinAboveMax { .. }
+        )
+    }
+}
+
+impl DressageTest {
+    pub fn validate(&self) -> Vec<TestDiagnostic> {
+        let test = self.name.clone();
+        let mut diagnostics = vec![];
+        if self.movements.is_empty() {
+            diagnostics.push(TestDiagnostic::NoMovements { test: test.clone() });
+        } else if self.total_marks() == dec!(0.0) {
+            diagnostics.push(TestDiagnostic::ZeroTotalMarks { test: test.clone() });
+        }
+        // a freestyle has no course to go off
+        if self.test_type != TestSheetType::Freestyle && self.errors_of_course.is_empty() {
+            diagnostics.push(TestDiagnostic::NoErrorsOfCourse { test: test.clone() });
+        }
+
+        let mut seen = HashSet::new();
+        for movement in self.movements.iter() {
+            let number = movement.number;
+            if !seen.insert(number) {
+                diagnostics.push(TestDiagnostic::DuplicateMovement {
+                    test: test.clone(),
+                    movement: number,
+                });
+            }
+            if movement.step == dec!(0.0) {
+                diagnostics.push(TestDiagnostic::ZeroStep {
+                    test: test.clone(),
+                    movement: number,
+                });
+            } else if movement.max % movement.step != dec!(0.0) {
+                diagnostics.push(TestDiagnostic::MaxOffStep {
+                    test: test.clone(),
+                    movement: number,
+                });
+            }
+            if movement.min > movement.max {
+                diagnostics.push(TestDiagnostic::MinAboveMax {
+                    test: test.clone(),
+                    movement: number,
+                });
+            }
+        }
+        diagnostics
+    }
+}
@@ -0,0 +1,41 @@
+use super::scoresheet::Scoresheet;
+
+/// The part of a scoresheet a command is about to change
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SheetEdit {
+    /// The mark, remark or attempts of a movement
+    Movement(u16),
+    Penalties,
+    Status,
+    /// The blood, lameness, equipment and meeting signals to the jury
+    Signal,
+}
+
+/// Why a change to a scoresheet was refused
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum EditRefused {
+    #[error("The marks have been confirmed and can no longer be changed")]
+    Locked,
+    #[error("Only the movements of the approved amendment can be changed, not movement {0}")]
+    NotAmendable(u16),
+    #[error("Too many marks are waiting to reach the server to take any more")]
+    OutboxFull,
+}
+
+impl Scoresheet {
+    /// Checks that the edit is allowed. Once the marks are confirmed only
+    /// the movements of an approved amendment may be marked again, until
+    /// the scorer unlocks the sheet.
+    pub fn check_edit(&self, edit: SheetEdit) -> Result<(), EditRefused> {
+        if !self.locked {
+            return Ok(());
+        }
+        match edit {
+            SheetEdit::Movement(number) if self.amendable().contains(&number) => Ok(()),
+            SheetEdit::Movement(number) if !self.amendable().is_empty() => {
+                Err(EditRefused::NotAmendable(number))
+            }
+            _ => Err(EditRefused::Locked),
+        }
+    }
+}
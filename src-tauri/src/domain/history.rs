@@ -0,0 +1,574 @@
+use decimal::Decimal;
+
+use super::deductions::PenaltySource;
+use super::dressage_test::DressageTest;
+use super::edit_guard::SheetEdit;
+use super::scoresheet::{ScoredMark, Scoresheet};
+use super::starter::{Starter, StarterResult};
+
+/// Consecutive keystrokes on the same mark within this many seconds are
+/// kept as a single change, so undo goes back to the previous mark rather
+/// than the previous digit.
+const COALESCE_SECONDS: i64 = 3;
+
+/// A single change the judge made to their scoresheet
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "t", rename_all = "camelCase")]
+pub enum SheetChange {
+    Mark {
+        number: u16,
+        #[serde(
+            default,
+            deserialize_with = "decimal::parsing::deserialize_opt_from_f64",
+            serialize_with = "decimal::parsing::serialize_opt_as_f64"
+        )]
+        from: Option<Decimal>,
+        #[serde(
+            default,
+            deserialize_with = "decimal::parsing::deserialize_opt_from_f64",
+            serialize_with = "decimal::parsing::serialize_opt_as_f64"
+        )]
+        to: Option<Decimal>,
+        /// Typed over the averaged attempts, so fair rounding leaves it alone
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        manual: bool,
+    },
+    Remark {
+        number: u16,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Attempts {
+        number: u16,
+        from: Vec<Decimal>,
+        to: Vec<Decimal>,
+    },
+    Penalty {
+        source: PenaltySource,
+        from: u8,
+        to: u8,
+    },
+    Status {
+        from: StarterResult,
+        to: StarterResult,
+    },
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub struct SheetEvent {
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// Everything changed by a single edit, such as a new attempt and the
+    /// marks which were rounded again because of it
+    pub changes: Vec<SheetChange>,
+}
+
+/// The sheet and the status of the starter which the events are played
+/// over, as they stood before the first event the judge can still undo.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Baseline {
+    pub scores: Vec<ScoredMark>,
+    pub errors: u8,
+    pub tech_penalties: u8,
+    pub art_penalties: u8,
+    pub status: StarterResult,
+}
+
+/// Every change made to a scoresheet in the order it was made. The judge's
+/// sheet is the base with the applied events played over it. The last
+/// `undone` events have been undone and can be redone, until a new change
+/// is made which discards them. The first `sealed` events are already part
+/// of the base, because the server changed the sheet after them, so they
+/// are kept for the audit trail but can no longer be undone.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct History {
+    pub events: Vec<SheetEvent>,
+    #[serde(default)]
+    pub undone: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<Baseline>,
+    #[serde(default)]
+    pub sealed: usize,
+}
+
+impl History {
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+    pub fn can_undo(&self) -> bool {
+        self.applied().len() > self.sealed
+    }
+    pub fn can_redo(&self) -> bool {
+        self.undone > 0
+    }
+    /// The events which make up the current sheet
+    pub fn applied(&self) -> &[SheetEvent] {
+        &self.events[..self.events.len() - self.undone]
+    }
+    /// The applied events which are not yet part of the base
+    fn replayed(&self) -> &[SheetEvent] {
+        &self.applied()[self.sealed..]
+    }
+    /// The event the next undo takes back
+    pub fn next_undo(&self) -> Option<&SheetEvent> {
+        self.can_undo().then(|| self.applied().last()).flatten()
+    }
+    /// The event the next redo puts back
+    pub fn next_redo(&self) -> Option<&SheetEvent> {
+        self.events.get(self.events.len() - self.undone)
+    }
+    /// Takes the sheet as it is now as the base, for when it has been
+    /// changed by something other than the judge's edits
+    fn rebase(&mut self, base: Baseline) {
+        self.base = Some(base);
+        self.sealed = self.applied().len();
+    }
+    fn record(&mut self, changes: Vec<SheetChange>) {
+        if changes.is_empty() {
+            return;
+        }
+        let at = chrono::Utc::now();
+        self.events.truncate(self.events.len() - self.undone);
+        self.undone = 0;
+        let coalescable = self.events.len() > self.sealed;
+        if let Some(last) = self.events.last_mut().filter(|_| coalescable) {
+            if let (
+                [SheetChange::Mark {
+                    number,
+                    from,
+                    to,
+                    manual,
+                }],
+                [SheetChange::Mark {
+                    number: next,
+                    to: next_to,
+                    manual: next_manual,
+                    ..
+                }],
+            ) = (last.changes.as_mut_slice(), changes.as_slice())
+            {
+                if number == next && (at - last.at).num_seconds() < COALESCE_SECONDS {
+                    // typed back to where it started, so nothing has changed
+                    if from == next_to {
+                        self.events.pop();
+                        return;
+                    }
+                    *to = *next_to;
+                    *manual = *next_manual;
+                    last.at = at;
+                    return;
+                }
+            }
+        }
+        self.events.push(SheetEvent { at, changes });
+    }
+}
+
+impl std::fmt::Display for SheetChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mark = |x: &Option<Decimal>| x.map_or("-".to_string(), |x| x.to_string());
+        let attempts = |x: &Vec<Decimal>| {
+            x.iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            Self::Mark {
+                number, from, to, ..
+            } => {
+                write!(f, "Movement {number} mark {} → {}", mark(from), mark(to))
+            }
+            Self::Remark { number, to, .. } => {
+                write!(
+                    f,
+                    "Movement {number} remark \"{}\"",
+                    to.as_deref().unwrap_or("")
+                )
+            }
+            Self::Attempts { number, from, to } => write!(
+                f,
+                "Movement {number} attempts [{}] → [{}]",
+                attempts(from),
+                attempts(to)
+            ),
+            Self::Penalty { source, from, to } => write!(f, "{source} {from} → {to}"),
+            Self::Status { from, to } => {
+                write!(f, "Status {} → {}", from.abbreviate(), to.abbreviate())
+            }
+        }
+    }
+}
+
+impl SheetChange {
+    /// The part of the sheet the change is to, for checking whether it may
+    /// still be undone or redone
+    pub fn edit(&self) -> SheetEdit {
+        match self {
+            Self::Mark { number, .. }
+            | Self::Remark { number, .. }
+            | Self::Attempts { number, .. } => SheetEdit::Movement(*number),
+            Self::Penalty { .. } => SheetEdit::Penalties,
+            Self::Status { .. } => SheetEdit::Status,
+        }
+    }
+}
+
+impl Baseline {
+    fn of(sheet: &Scoresheet, status: &StarterResult) -> Self {
+        Self {
+            scores: sheet.scores.clone(),
+            errors: sheet.errors,
+            tech_penalties: sheet.tech_penalties,
+            art_penalties: sheet.art_penalties,
+            status: status.clone(),
+        }
+    }
+}
+
+/// The judge's scoresheet and the status of the starter before an edit
+pub struct Snapshot {
+    sheet: Option<Scoresheet>,
+    status: StarterResult,
+}
+
+impl Starter {
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            sheet: self.scoresheets.first().cloned(),
+            status: self.status.clone(),
+        }
+    }
+
+    /// Records the differences between the snapshot and the starter now as
+    /// a single event, then derives the sheet from the history again
+    pub fn record_since(&mut self, snapshot: Snapshot) {
+        let Snapshot {
+            sheet: Some(before),
+            status,
+        } = snapshot
+        else {
+            return;
+        };
+        let Some(sheet) = self.scoresheets.first_mut() else {
+            return;
+        };
+        // the sheet as the server gave it, before the judge's first edit
+        if sheet.history.base.is_none() {
+            sheet.history.rebase(Baseline::of(&before, &status));
+        }
+        let changes = changes(&before, sheet, &status, &self.status);
+        sheet.history.record(changes);
+        self.rebuild();
+    }
+
+    /// Takes the judge's sheet as it is now as the base of its history, as
+    /// the server has changed it outside of the judge's edits
+    pub fn rebase_history(&mut self) {
+        let status = self.status.clone();
+        if let Some(sheet) = self.scoresheets.first_mut() {
+            let base = Baseline::of(sheet, &status);
+            sheet.history.rebase(base);
+        }
+    }
+
+    /// Takes back the last edit still applied, returning what changed so
+    /// that it can be shown on screen and sent on to the server.
+    pub fn undo(&mut self, test: &DressageTest) -> Option<Vec<SheetChange>> {
+        let sheet = self.scoresheets.first_mut()?;
+        if !sheet.history.can_undo() {
+            return None;
+        }
+        sheet.history.undone += 1;
+        Some(self.replay_history(test))
+    }
+
+    /// Puts back the most recently undone edit
+    pub fn redo(&mut self, test: &DressageTest) -> Option<Vec<SheetChange>> {
+        let sheet = self.scoresheets.first_mut()?;
+        if !sheet.history.can_redo() {
+            return None;
+        }
+        sheet.history.undone -= 1;
+        Some(self.replay_history(test))
+    }
+
+    /// Derives the sheet from the events now applied. Attempts put back by
+    /// an undo or redo change how every other movement is fairly rounded,
+    /// so the pass is run again over the whole sheet.
+    fn replay_history(&mut self, test: &DressageTest) -> Vec<SheetChange> {
+        let snapshot = self.snapshot();
+        self.rebuild();
+        if let Some(sheet) = self.scoresheets.first_mut() {
+            sheet.fair_round_attempts(test);
+        }
+        match (snapshot.sheet, self.scoresheets.first()) {
+            (Some(before), Some(after)) => changes(&before, after, &snapshot.status, &self.status),
+            _ => vec![],
+        }
+    }
+
+    /// Sets the judge's sheet and the status of the starter to the base of
+    /// the history with the applied events played over it
+    fn rebuild(&mut self) {
+        let Some(sheet) = self.scoresheets.first_mut() else {
+            return;
+        };
+        if let Some((replayed, status)) = sheet.history.replay(sheet) {
+            *sheet = replayed;
+            self.status = status;
+        }
+    }
+}
+
+impl History {
+    /// Plays the events not yet part of the base over it, giving the sheet
+    /// and the status of the starter they describe. Nothing has been
+    /// recorded without a base, so there is nothing to play.
+    fn replay(&self, sheet: &Scoresheet) -> Option<(Scoresheet, StarterResult)> {
+        let base = self.base.as_ref()?;
+        let mut sheet = Scoresheet {
+            scores: base.scores.clone(),
+            errors: base.errors,
+            tech_penalties: base.tech_penalties,
+            art_penalties: base.art_penalties,
+            ..sheet.clone()
+        };
+        let mut status = base.status.clone();
+        for change in self.replayed().iter().flat_map(|x| x.changes.iter()) {
+            match change {
+                SheetChange::Mark {
+                    number, to, manual, ..
+                } => {
+                    let scored = scored_mut(&mut sheet, *number);
+                    scored.mark = *to;
+                    scored.manually_set = *manual;
+                }
+                SheetChange::Remark { number, to, .. } => {
+                    scored_mut(&mut sheet, *number).remark = to.clone()
+                }
+                // new attempts release the mark back to fair rounding
+                SheetChange::Attempts { number, to, .. } => {
+                    let scored = scored_mut(&mut sheet, *number);
+                    scored.attempts = to.clone();
+                    scored.manually_set = false;
+                }
+                SheetChange::Penalty { source, to, .. } => match source {
+                    PenaltySource::ErrorsOfCourse => sheet.errors = *to,
+                    PenaltySource::TechnicalPenalties => sheet.tech_penalties = *to,
+                    PenaltySource::ArtisticPenalties => sheet.art_penalties = *to,
+                },
+                SheetChange::Status { to, .. } => status = to.clone(),
+            }
+        }
+        Some((sheet, status))
+    }
+}
+
+impl Scoresheet {
+    /// The sheet as the judge's edits describe it, which is the sheet itself
+    /// when nothing has been recorded yet
+    pub fn replay(&self) -> Scoresheet {
+        self.history
+            .replay(self)
+            .map_or_else(|| self.clone(), |(sheet, _)| sheet)
+    }
+}
+
+fn scored_mut(sheet: &mut Scoresheet, number: u16) -> &mut ScoredMark {
+    match sheet.scores.iter().position(|x| x.number == number) {
+        Some(i) => &mut sheet.scores[i],
+        None => {
+            sheet.scores.push(ScoredMark::new(number));
+            sheet.scores.last_mut().expect("We just added this")
+        }
+    }
+}
+
+fn changes(
+    before: &Scoresheet,
+    after: &Scoresheet,
+    status: &StarterResult,
+    next_status: &StarterResult,
+) -> Vec<SheetChange> {
+    let mut changes = vec![];
+    let blank = ScoredMark::new(0);
+    for scored in after.scores.iter() {
+        let number = scored.number;
+        let previous = before
+            .scores
+            .iter()
+            .find(|x| x.number == number)
+            .unwrap_or(&blank);
+        if previous.attempts != scored.attempts {
+            changes.push(SheetChange::Attempts {
+                number,
+                from: previous.attempts.clone(),
+                to: scored.attempts.clone(),
+            });
+        }
+        if previous.mark != scored.mark {
+            changes.push(SheetChange::Mark {
+                number,
+                from: previous.mark,
+                to: scored.mark,
+                manual: scored.manually_set,
+            });
+        }
+        if previous.remark != scored.remark {
+            changes.push(SheetChange::Remark {
+                number,
+                from: previous.remark.clone(),
+                to: scored.remark.clone(),
+            });
+        }
+    }
+    for (source, from, to) in [
+        (PenaltySource::ErrorsOfCourse, before.errors, after.errors),
+        (
+            PenaltySource::TechnicalPenalties,
+            before.tech_penalties,
+            after.tech_penalties,
+        ),
+        (
+            PenaltySource::ArtisticPenalties,
+            before.art_penalties,
+            after.art_penalties,
+        ),
+    ] {
+        if from != to {
+            changes.push(SheetChange::Penalty { source, from, to });
+        }
+    }
+    if status != next_status {
+        changes.push(SheetChange::Status {
+            from: status.clone(),
+            to: next_status.clone(),
+        });
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal::{dec, Decimal};
+
+    use super::{Baseline, History, SheetChange};
+    use crate::domain::scoresheet::{ScoredMark, Scoresheet};
+    use crate::domain::starter::StarterResult;
+    use crate::domain::SurrealId;
+
+    fn sheet(history: History) -> Scoresheet {
+        Scoresheet {
+            id: SurrealId::make("scoresheet", "test"),
+            score: None,
+            rank: None,
+            errors: 0,
+            tech_penalties: 0,
+            art_penalties: 0,
+            scores: vec![],
+            summary: None,
+            notes: None,
+            warning_manager: Default::default(),
+            locked: false,
+            test: None,
+            ground_jury_member: None,
+            history,
+            amendments: vec![],
+        }
+    }
+
+    fn base(scores: Vec<ScoredMark>) -> Baseline {
+        Baseline {
+            scores,
+            errors: 0,
+            tech_penalties: 0,
+            art_penalties: 0,
+            status: StarterResult::InProgress(0),
+        }
+    }
+
+    fn mark(number: u16, from: Option<Decimal>, to: Option<Decimal>) -> SheetChange {
+        SheetChange::Mark {
+            number,
+            from,
+            to,
+            manual: false,
+        }
+    }
+
+    fn mark_of(sheet: &Scoresheet, number: u16) -> Option<Decimal> {
+        sheet
+            .scores
+            .iter()
+            .find(|x| x.number == number)
+            .and_then(|x| x.mark)
+    }
+
+    #[test]
+    fn keystrokes_on_a_mark_are_one_event() {
+        let mut history = History::default();
+        history.record(vec![mark(1, None, Some(dec!(7.0)))]);
+        history.record(vec![mark(1, Some(dec!(7.0)), Some(dec!(7.5)))]);
+        assert_eq!(history.events.len(), 1);
+        assert_eq!(
+            history.events[0].changes,
+            vec![mark(1, None, Some(dec!(7.5)))]
+        );
+    }
+
+    #[test]
+    fn typing_a_mark_back_leaves_no_event() {
+        let mut history = History::default();
+        history.record(vec![mark(1, Some(dec!(6.0)), Some(dec!(7.0)))]);
+        history.record(vec![mark(1, Some(dec!(7.0)), Some(dec!(6.0)))]);
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn the_sheet_is_the_base_and_the_applied_events() {
+        let mut history = History {
+            base: Some(base(vec![ScoredMark {
+                mark: Some(dec!(5.0)),
+                ..ScoredMark::new(1)
+            }])),
+            ..Default::default()
+        };
+        history.record(vec![mark(1, Some(dec!(5.0)), Some(dec!(6.0)))]);
+        history.record(vec![
+            mark(2, None, Some(dec!(8.0))),
+            SheetChange::Status {
+                from: StarterResult::InProgress(0),
+                to: StarterResult::Retired,
+            },
+        ]);
+
+        let (replayed, status) = history.replay(&sheet(history.clone())).expect("A base");
+        assert_eq!(mark_of(&replayed, 1), Some(dec!(6.0)));
+        assert_eq!(mark_of(&replayed, 2), Some(dec!(8.0)));
+        assert_eq!(status, StarterResult::Retired);
+
+        history.undone = 2;
+        let (replayed, status) = history.replay(&sheet(history.clone())).expect("A base");
+        assert_eq!(mark_of(&replayed, 1), Some(dec!(5.0)));
+        assert_eq!(mark_of(&replayed, 2), None);
+        assert_eq!(status, StarterResult::InProgress(0));
+    }
+
+    #[test]
+    fn changes_from_the_server_cannot_be_undone() {
+        let mut history = History::default();
+        history.record(vec![mark(1, None, Some(dec!(7.0)))]);
+        assert!(history.can_undo());
+
+        history.rebase(base(vec![ScoredMark {
+            mark: Some(dec!(6.5)),
+            ..ScoredMark::new(1)
+        }]));
+        assert!(!history.can_undo());
+        // still part of the audit trail
+        assert_eq!(history.applied().len(), 1);
+
+        let (replayed, _) = history.replay(&sheet(history.clone())).expect("A base");
+        assert_eq!(mark_of(&replayed, 1), Some(dec!(6.5)));
+    }
+}
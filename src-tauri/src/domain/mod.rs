@@ -1,10 +1,18 @@
+pub mod amendment;
 pub mod competition;
 pub mod competitor;
+pub mod deductions;
+pub mod deviation;
 pub mod dressage_test;
+pub mod edit_guard;
 pub mod ground_jury_member;
+pub mod history;
 pub mod judge;
+pub mod panel;
 pub mod penalties;
 pub mod position;
+pub mod ranking;
+pub mod remark_policy;
 pub mod scoresheet;
 pub mod show;
 pub mod starter;
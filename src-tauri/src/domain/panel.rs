@@ -0,0 +1,126 @@
+use decimal::{Decimal, RoundingMode};
+
+use super::competition::Competition;
+use super::dressage_test::{DressageTest, Precision};
+use super::ground_jury_member::{GroundJuryMember, JuryAuthority};
+use super::position::Position;
+use super::scoresheet::Scoresheet;
+use super::starter::Starter;
+use super::SurrealId;
+
+/// The combined result of every judge's scoresheet for a starter
+#[derive(Clone, Debug)]
+pub struct Panel {
+    pub score: Option<Decimal>,
+    /// Some of the counted sheets are still open, so the score is made up
+    /// of running trends rather than only final scores
+    pub provisional: bool,
+    pub contributions: Vec<Contribution>,
+}
+
+/// The score given by a single member of the ground jury, and whether
+/// it was counted towards the panel score.
+#[derive(Clone, Debug)]
+pub struct Contribution {
+    pub sheet_id: SurrealId,
+    pub position: Position,
+    pub authority: JuryAuthority,
+    pub score: Option<Decimal>,
+    pub counted: bool,
+    /// The score is the final score of a locked sheet
+    pub locked: bool,
+}
+
+impl Starter {
+    /// Calculates the panel score locally from the scoresheets which belong
+    /// to the actual jury, so that Shadow, Observer and Removed members never
+    /// change the result. Locked sheets give their final score and open
+    /// sheets give their running trend, which makes the panel provisional.
+    pub fn panel(&self, competition: &Competition) -> Panel {
+        let contributions = self
+            .jury_sheets(competition)
+            .map(|(sheet, member, test)| {
+                let score = match sheet.scores.iter().any(|x| x.mark.is_some()) {
+                    true if sheet.locked => Some(sheet.calculate_score(test)),
+                    true => Some(sheet.calculate_trend(test)),
+                    false => sheet.score,
+                };
+                Contribution {
+                    sheet_id: sheet.id.clone(),
+                    position: member.position.clone(),
+                    authority: member.authority.clone(),
+                    score,
+                    counted: member.authority.is_actual_jury() && score.is_some(),
+                    locked: sheet.locked,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let scores = contributions
+            .iter()
+            .filter(|x| x.counted)
+            .filter_map(|x| x.score)
+            .collect::<Vec<_>>();
+        Panel {
+            score: Decimal::average(&scores, 6, RoundingMode::HalfUp)
+                .map(|x| self.precision(competition).round(x)),
+            provisional: contributions.iter().any(|x| x.counted && !x.locked),
+            contributions,
+        }
+    }
+
+    /// The precision the starter's scores are published at, from the test
+    /// their sheets are marked on rather than the competition's first one
+    pub fn precision(&self, competition: &Competition) -> Precision {
+        self.jury_sheets(competition)
+            .map(|(_, _, test)| test.precision)
+            .next()
+            .unwrap_or_else(|| competition.precision())
+    }
+
+    /// Each scoresheet alongside the member of the jury who marked it and
+    /// the test they marked.
+    pub fn jury_sheets<'a>(
+        &'a self,
+        competition: &'a Competition,
+    ) -> impl Iterator<Item = (&'a Scoresheet, &'a GroundJuryMember, &'a DressageTest)> {
+        self.scoresheets
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, sheet)| {
+                let member = find_member(competition, sheet.ground_jury_member.as_ref(), i)?;
+                let test = sheet
+                    .test
+                    .as_ref()
+                    .unwrap_or_else(|| competition.get_test(member));
+                Some((sheet, member, test))
+            })
+    }
+
+    /// The position of the judge who marks the scoresheet
+    pub fn sheet_position(
+        &self,
+        competition: &Competition,
+        sheet_id: &ulid::Ulid,
+    ) -> Option<Position> {
+        self.jury_sheets(competition)
+            .find(|(sheet, ..)| sheet.id.ulid() == *sheet_id)
+            .map(|(_, member, _)| member.position.clone())
+    }
+}
+
+/// Scoresheets are matched to the jury by their ground jury member. Guessing
+/// from the order of the jury could give a sheet to the wrong judge, or count
+/// a Shadow, so other sheets without a member are left out. The first sheet
+/// is always the judge's own, which belongs to the first member of the jury.
+fn find_member<'a>(
+    competition: &'a Competition,
+    id: Option<&SurrealId>,
+    index: usize,
+) -> Option<&'a GroundJuryMember> {
+    match id {
+        Some(id) => competition.jury.iter().find(|x| x.id == *id),
+        None if index == 0 => competition.jury.first(),
+        None => None,
+    }
+}
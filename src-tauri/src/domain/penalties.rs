@@ -100,7 +100,7 @@ pub enum PenaltyType {
     Elimination,
 }
 
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub enum BroadcastPenaltyVariety {
     #[serde(alias = "errors")]
     ErrorsOfCourse,
@@ -109,3 +109,13 @@ pub enum BroadcastPenaltyVariety {
     #[serde(alias = "technical")]
     TechnicalPenalty,
 }
+impl From<super::deductions::PenaltySource> for BroadcastPenaltyVariety {
+    fn from(value: super::deductions::PenaltySource) -> Self {
+        use super::deductions::PenaltySource;
+        match value {
+            PenaltySource::ErrorsOfCourse => Self::ErrorsOfCourse,
+            PenaltySource::TechnicalPenalties => Self::TechnicalPenalty,
+            PenaltySource::ArtisticPenalties => Self::ArtisticPenalty,
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use decimal::{dec, Decimal, RoundingMode};
+
+use super::competition::Competition;
+use super::dressage_test::{DressageTest, MovementCategory, TestSheetType};
+use super::starter::Starter;
+
+/// How starters on the same score are separated
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum TieBreak {
+    /// Starters on the same score share the placing
+    Shared,
+    /// The higher total of collective marks across the jury is placed first
+    CollectiveMarks,
+    /// The higher artistic percentage across the jury is placed first
+    ArtisticScore,
+}
+impl TieBreak {
+    /// The tie break when the competition does not choose one
+    pub fn for_test(test: &DressageTest) -> Self {
+        match test.test_type {
+            TestSheetType::Freestyle => Self::ArtisticScore,
+            TestSheetType::Normal | TestSheetType::Quality => Self::CollectiveMarks,
+        }
+    }
+
+    fn value(&self, starter: &Starter, competition: &Competition) -> Decimal {
+        let sheets = starter
+            .jury_sheets(competition)
+            .filter(|(_, member, _)| member.authority.is_actual_jury());
+        match self {
+            Self::Shared => dec!(0.0),
+            Self::CollectiveMarks => sheets.fold(dec!(0.0), |sum, (sheet, _, test)| {
+                test.movements
+                    .iter()
+                    .filter(|x| x.category == MovementCategory::Collective)
+                    .filter_map(|movement| {
+                        sheet
+                            .scores
+                            .iter()
+                            .find(|x| x.number == movement.number as u16)
+                            .and_then(|x| x.mark)
+                            .map(|mark| mark * movement.coefficient)
+                    })
+                    .fold(sum, |sum, mark| sum + mark)
+            }),
+            Self::ArtisticScore => {
+                let scores = sheets
+                    .filter_map(|(sheet, _, test)| sheet.trend_breakdown(test))
+                    .map(|x| x.artistic)
+                    .collect::<Vec<_>>();
+                Decimal::average(&scores, 3, RoundingMode::HalfUp).unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Provisional placings worked out on the device, so that they are
+/// available while the server is unreachable.
+#[derive(Clone, Debug, Default)]
+pub struct Ranking(HashMap<String, u16>);
+impl Ranking {
+    pub fn rank(&self, starter: &Starter) -> Option<u16> {
+        self.0.get(&starter.id.to_string()).copied()
+    }
+}
+
+impl Competition {
+    pub fn ranking(&self) -> Ranking {
+        let tie_break = self
+            .tie_break
+            .or_else(|| self.tests.first().map(TieBreak::for_test))
+            .unwrap_or(TieBreak::Shared);
+        self.ranking_with(tie_break)
+    }
+
+    /// Ranks every starter who is still able to be placed by their panel
+    /// score. Starters who cannot be separated by the tie break share the
+    /// placing, and the following placing is skipped.
+    pub fn ranking_with(&self, tie_break: TieBreak) -> Ranking {
+        let mut ranked = self
+            .starters
+            .iter()
+            .filter(|starter| starter.status.is_rankable())
+            .filter_map(|starter| {
+                let score = starter.panel(self).score.or(starter.score)?;
+                Some((starter, score, tie_break.value(starter, self)))
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|(_, a, a_tie), (_, b, b_tie)| {
+            b.partial_cmp(a)
+                .unwrap_or(Ordering::Equal)
+                .then(b_tie.partial_cmp(a_tie).unwrap_or(Ordering::Equal))
+        });
+
+        let mut ranks = HashMap::new();
+        let mut previous = None;
+        let mut rank = 0;
+        for (i, (starter, score, tie)) in ranked.into_iter().enumerate() {
+            if previous != Some((score, tie)) {
+                rank = i as u16 + 1;
+            }
+            previous = Some((score, tie));
+            ranks.insert(starter.id.to_string(), rank);
+        }
+        Ranking(ranks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use decimal::{dec, Decimal};
+
+    use super::TieBreak;
+    use crate::domain::competition::Competition;
+    use crate::domain::dressage_test::{DressageTest, Exercise};
+    use crate::domain::scoresheet::{ScoredMark, Scoresheet};
+    use crate::domain::starter::{Starter, StarterResult};
+    use crate::domain::SurrealId;
+
+    fn movement(number: u8, category: &str) -> Exercise {
+        serde_json::from_value(serde_json::json!({"nr": number, "ct": category}))
+            .expect("A valid movement")
+    }
+
+    /// A test of one technical movement followed by one collective mark
+    fn test() -> DressageTest {
+        DressageTest {
+            movements: vec![movement(1, "T"), movement(2, "C")],
+            ..Default::default()
+        }
+    }
+
+    fn competition(starters: Vec<Starter>) -> Competition {
+        let mut competition: Competition = serde_json::from_value(serde_json::json!({
+            "id": "competition:ranking",
+            "name": "Ranking",
+            "startTime": "2026-01-01T09:00:00Z",
+            "arena": null,
+            "tests": [],
+            "jury": [{
+                "id": "groundJuryMember:c",
+                "position": "C",
+                "judge": {
+                    "id": "judge:c",
+                    "firstName": "",
+                    "lastName": "",
+                    "user": null,
+                    "signature": null,
+                    "prefs": {},
+                },
+                "authority": "Chief",
+            }],
+            "starters": [],
+        }))
+        .expect("A valid competition");
+        competition.tests = vec![test()];
+        competition.starters = starters;
+        competition
+    }
+
+    fn starter(id: &str, status: StarterResult, score: Option<Decimal>) -> Starter {
+        let mut starter: Starter = serde_json::from_value(serde_json::json!({
+            "id": format!("starter:{id}"),
+            "competitor": {
+                "id": format!("competitor:{id}"),
+                "firstName": "",
+                "lastName": "",
+                "horseName": "",
+                "compNo": "",
+            },
+            "score": null,
+            "status": ["Upcoming"],
+            "startTime": "2026-01-01T09:00:00Z",
+            "number": 1,
+            "index": 1,
+            "scoresheets": [],
+        }))
+        .expect("A valid starter");
+        starter.status = status;
+        starter.score = score;
+        starter
+    }
+
+    /// A starter marked by the chief judge on the technical movement and
+    /// the collective mark
+    fn marked(id: &str, technical: Decimal, collective: Decimal) -> Starter {
+        let mut starter = starter(id, StarterResult::InProgress(0), None);
+        starter.scoresheets = vec![Scoresheet {
+            id: SurrealId::make("scoresheet", id),
+            score: None,
+            rank: None,
+            errors: 0,
+            tech_penalties: 0,
+            art_penalties: 0,
+            scores: vec![
+                ScoredMark {
+                    mark: Some(technical),
+                    ..ScoredMark::new(1)
+                },
+                ScoredMark {
+                    mark: Some(collective),
+                    ..ScoredMark::new(2)
+                },
+            ],
+            summary: None,
+            notes: None,
+            warning_manager: Default::default(),
+            locked: false,
+            test: None,
+            ground_jury_member: None,
+            history: Default::default(),
+            amendments: vec![],
+        }];
+        starter
+    }
+
+    fn placed(id: &str, score: Decimal) -> Starter {
+        starter(id, StarterResult::Placed(0), Some(score))
+    }
+
+    fn ranks(competition: &Competition, tie_break: TieBreak) -> Vec<Option<u16>> {
+        let ranking = competition.ranking_with(tie_break);
+        competition
+            .starters
+            .iter()
+            .map(|x| ranking.rank(x))
+            .collect()
+    }
+
+    #[test]
+    fn shared_placings_skip_the_next_rank() {
+        let competition = competition(vec![
+            placed("a", dec!(70.0)),
+            placed("b", dec!(72.5)),
+            placed("c", dec!(70.0)),
+            placed("d", dec!(65.0)),
+        ]);
+        assert_eq!(
+            ranks(&competition, TieBreak::Shared),
+            vec![Some(2), Some(1), Some(2), Some(4)]
+        );
+    }
+
+    #[test]
+    fn the_tie_break_orders_equal_scores() {
+        let competition = competition(vec![
+            marked("a", dec!(8.0), dec!(6.0)),
+            marked("b", dec!(6.0), dec!(8.0)),
+        ]);
+        assert_eq!(
+            ranks(&competition, TieBreak::CollectiveMarks),
+            vec![Some(2), Some(1)]
+        );
+        assert_eq!(
+            ranks(&competition, TieBreak::Shared),
+            vec![Some(1), Some(1)]
+        );
+    }
+
+    #[test]
+    fn the_competition_chooses_the_tie_break() {
+        let mut competition = competition(vec![
+            marked("a", dec!(8.0), dec!(6.0)),
+            marked("b", dec!(6.0), dec!(8.0)),
+        ]);
+        assert_eq!(
+            competition.ranking().rank(&competition.starters[1]),
+            Some(1)
+        );
+
+        competition.tie_break = Some(TieBreak::Shared);
+        assert_eq!(
+            competition.ranking().rank(&competition.starters[0]),
+            Some(1)
+        );
+        assert_eq!(
+            competition.ranking().rank(&competition.starters[1]),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn starters_who_did_not_finish_are_left_out() {
+        let competition = competition(vec![
+            starter(
+                "e",
+                StarterResult::Eliminated(String::new()),
+                Some(dec!(80.0)),
+            ),
+            starter("w", StarterResult::Withdrawn, Some(dec!(80.0))),
+            starter("n", StarterResult::NoShow, Some(dec!(80.0))),
+            starter("r", StarterResult::Retired, Some(dec!(80.0))),
+            starter("d", StarterResult::Disqualified, Some(dec!(80.0))),
+            starter("u", StarterResult::Upcoming, Some(dec!(80.0))),
+            placed("p", dec!(60.0)),
+        ]);
+        assert_eq!(
+            ranks(&competition, TieBreak::Shared),
+            vec![None, None, None, None, None, None, Some(1)]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use decimal::{dec, Decimal};
+
+use super::dressage_test::{DressageTest, MovementCategory};
+use super::scoresheet::Scoresheet;
+
+/// Marks at or below the threshold must be given with a written remark
+/// before the sheet can be confirmed, for movements of the listed categories.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemarkPolicy {
+    #[serde(
+        default,
+        deserialize_with = "decimal::parsing::deserialize_opt_from_f64",
+        serialize_with = "decimal::parsing::serialize_opt_as_f64"
+    )]
+    pub threshold: Option<Decimal>,
+    /// Left out, like the threshold, no movement needs a remark
+    #[serde(default)]
+    pub categories: Vec<MovementCategory>,
+}
+
+impl Default for RemarkPolicy {
+    fn default() -> Self {
+        use MovementCategory::*;
+        Self {
+            threshold: Some(dec!(4.0)),
+            categories: vec![Technical, Artistic, Collective, Joker, Acceptable],
+        }
+    }
+}
+
+impl Scoresheet {
+    /// The movements whose mark needs a remark under the policy, but has none
+    pub fn missing_remarks(&self, test: &DressageTest, policy: &RemarkPolicy) -> Vec<u8> {
+        let Some(threshold) = policy.threshold else {
+            return vec![];
+        };
+        test.movements
+            .iter()
+            .filter(|movement| policy.categories.contains(&movement.category))
+            .filter(|movement| {
+                self.scores
+                    .iter()
+                    .find(|x| x.number == movement.number as u16)
+                    .is_some_and(|x| {
+                        x.mark.is_some_and(|mark| mark <= threshold)
+                            && x.remark.as_ref().is_none_or(|r| r.trim().is_empty())
+                    })
+            })
+            .map(|movement| movement.number)
+            .collect()
+    }
+}
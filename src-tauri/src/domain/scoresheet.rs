@@ -1,8 +1,12 @@
+use std::cmp::Ordering;
+
 use crate::commands::warnings::manager::Warnings;
-use decimal::{dec, Decimal};
+use decimal::{dec, Decimal, RoundingMode};
 
-use super::dressage_test::DressageTest;
-use super::penalties::PenaltyType;
+use super::amendment::Amendment;
+use super::deductions::PenaltySource;
+use super::dressage_test::{Counting, DressageTest, Precision, TestSheetType};
+use super::history::History;
 use super::SurrealId;
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -26,9 +30,15 @@ pub struct Scoresheet {
     pub locked: bool,
     #[serde(default)]
     pub test: Option<DressageTest>,
+    #[serde(default)]
+    pub ground_jury_member: Option<SurrealId>,
+    #[serde(default, skip_serializing_if = "History::is_empty")]
+    pub history: History,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amendments: Vec<Amendment>,
 }
 
-#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
 pub struct ScoredMark {
     #[serde(rename = "nr")]
     pub number: u16,
@@ -43,6 +53,10 @@ pub struct ScoredMark {
     pub remark: Option<String>,
     #[serde(rename = "at", default, skip_serializing_if = "Vec::is_empty")]
     pub attempts: Vec<Decimal>,
+    /// The judge typed the mark over the top of the averaged attempts,
+    /// so fair rounding must leave it alone.
+    #[serde(rename = "ms", default, skip_serializing_if = "std::ops::Not::not")]
+    pub manually_set: bool,
 }
 
 impl ScoredMark {
@@ -52,76 +66,203 @@ impl ScoredMark {
             mark: None,
             remark: None,
             attempts: vec![],
+            manually_set: false,
         }
     }
 }
 impl Scoresheet {
     pub fn deductions(&self, test: &DressageTest) -> Decimal {
-        let total_marks = test.total_marks();
-        let mut points_deduction = dec!(0.0);
-        let mut percent_deduction = dec!(0.000);
-        for i in 0..self.errors {
-            let pen =
-                &test.errors_of_course[usize::min(i as usize, test.errors_of_course.len() - 1)];
-            match pen.ty {
-                PenaltyType::Percentage(num) => percent_deduction += num,
-                PenaltyType::Points(num) => points_deduction += num,
-                PenaltyType::Elimination => (),
-            }
-        }
-        for i in 0..self.tech_penalties {
-            let pen = &test.technical_penalties
-                [usize::min(i as usize, test.technical_penalties.len() - 1)];
-            match pen.ty {
-                PenaltyType::Percentage(num) => percent_deduction += num,
-                PenaltyType::Points(num) => points_deduction += num,
-                PenaltyType::Elimination => (),
-            }
-        }
-        for i in 0..self.art_penalties {
-            let pen =
-                &test.artistic_penalties[usize::min(i as usize, test.artistic_penalties.len() - 1)];
-            match pen.ty {
-                PenaltyType::Percentage(num) => percent_deduction += num,
-                PenaltyType::Points(num) => points_deduction += num,
-                PenaltyType::Elimination => (),
-            }
-        }
-        (points_deduction
-            * dec!(100.000)
-                .safe_divide(total_marks, 3)
-                .expect("To divide"))
-            + percent_deduction
+        self.deduction_report(test).total()
+    }
+
+    /// The reason for an elimination if any of the penalty counts have reached
+    /// an elimination in the test sheet's penalty schedule.
+    pub fn elimination(&self, test: &DressageTest) -> Option<String> {
+        self.deduction_report(test)
+            .elimination()
+            .map(|pen| format!("{} {}", pen.source, pen.count))
     }
 
+    /// The running trend, which only counts the movements marked so far
     pub fn calculate_trend(&self, testsheet: &DressageTest) -> Decimal {
+        let (total, max_total) = self.tally(testsheet, false);
+        self.percentage(total, max_total, testsheet)
+    }
+
+    /// The score of the completed test, where movements which are always
+    /// counted still count against the maximum if they were left unmarked
+    pub fn calculate_score(&self, testsheet: &DressageTest) -> Decimal {
+        let (total, max_total) = self.tally(testsheet, true);
+        self.percentage(total, max_total, testsheet)
+    }
+
+    fn percentage(&self, total: Decimal, max_total: Decimal, testsheet: &DressageTest) -> Decimal {
+        let perc = total.safe_divide(max_total, 6).unwrap_or_default() * dec!(100);
+        testsheet.precision.round(perc - self.deductions(testsheet))
+    }
+
+    /// Sums the marks and the maximum marks of each movement according to
+    /// how its category is counted.
+    fn tally(&self, testsheet: &DressageTest, complete: bool) -> (Decimal, Decimal) {
         let mut total = dec!(0.0);
         let mut max_total = dec!(0.0);
-        let mut scale = 1;
 
         for movement in testsheet.movements.iter() {
-            let Some(exercise) = self
+            let mark = self
                 .scores
                 .iter()
                 .find(|x| x.number == movement.number as u16)
+                .and_then(|x| x.mark);
+            let counted = match movement.category.counting() {
+                Counting::Always => complete || mark.is_some(),
+                Counting::WhenMarked => mark.is_some(),
+                Counting::Never => false,
+            };
+            if !counted {
+                continue;
+            }
+            total += mark.unwrap_or_default() * movement.coefficient;
+            max_total += movement.max * movement.coefficient;
+        }
+        (total, max_total)
+    }
+}
+
+/// Split of a freestyle trend into its technical and artistic parts.
+/// Each percentage already has its own penalties taken off.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrendBreakdown {
+    pub technical: Decimal,
+    pub artistic: Decimal,
+    pub total: Decimal,
+    pub errors_deduction: Decimal,
+    pub technical_deduction: Decimal,
+    pub artistic_deduction: Decimal,
+    pub precision: Precision,
+}
+
+impl Scoresheet {
+    /// Only freestyle tests publish a technical and artistic split, so other
+    /// test types return `None`.
+    pub fn trend_breakdown(&self, test: &DressageTest) -> Option<TrendBreakdown> {
+        if test.test_type != TestSheetType::Freestyle {
+            return None;
+        }
+        let mut technical = (dec!(0.0), dec!(0.0));
+        let mut artistic = (dec!(0.0), dec!(0.0));
+
+        for movement in test.movements.iter() {
+            if movement.category.counting() == Counting::Never {
+                continue;
+            }
+            let side = match movement.category.is_artistic() {
+                true => &mut artistic,
+                false => &mut technical,
+            };
+            let Some(mark) = self
+                .scores
+                .iter()
+                .find(|x| x.number == movement.number as u16)
+                .and_then(|x| x.mark)
+            else {
+                continue;
+            };
+            side.0 += mark * movement.coefficient;
+            side.1 += movement.max * movement.coefficient;
+        }
+
+        // the same deductions as the report, so one penalty shows one figure
+        let report = self.deduction_report(test);
+        let technical_deduction = report.source_total(PenaltySource::TechnicalPenalties);
+        let artistic_deduction = report.source_total(PenaltySource::ArtisticPenalties);
+        let errors_deduction = report.source_total(PenaltySource::ErrorsOfCourse);
+        let percentage = |(total, max): (Decimal, Decimal)| {
+            total.safe_divide(max, 6).unwrap_or_default() * dec!(100)
+        };
+
+        Some(TrendBreakdown {
+            technical: test
+                .precision
+                .round(percentage(technical) - technical_deduction),
+            artistic: test
+                .precision
+                .round(percentage(artistic) - artistic_deduction),
+            total: self.calculate_trend(test),
+            errors_deduction,
+            technical_deduction,
+            artistic_deduction,
+            precision: test.precision,
+        })
+    }
+
+    /// Averages the attempts of every movement using the fair rounding rule
+    /// and returns the marks which were changed by it.
+    /// 1. Take all marks which cannot be evenly averaged to the movement step.
+    /// 2. Sort them by highest to lowest coefficient, then by movement number.
+    /// 3. Alternately round up and down, starting with round up.
+    /// 4. Skip any marks which have been manually set, so the judge doesn't
+    ///    have marks dancing around after they have already typed one in.
+    pub fn fair_round_attempts(&mut self, test: &DressageTest) -> Vec<ScoredMark> {
+        let mut changed = vec![];
+        let mut uneven = vec![];
+        for movement in test.movements.iter() {
+            let Some(scored) = self
+                .scores
+                .iter_mut()
+                .find(|x| x.number == movement.number as u16)
+            else {
+                continue;
+            };
+            if scored.manually_set {
+                continue;
+            }
+            let Some(average) = Decimal::average(&scored.attempts, 6, RoundingMode::HalfUp) else {
+                continue;
+            };
+            let (down, up) = step_bounds(average, movement.step);
+            if down == up {
+                if scored.mark != Some(up) {
+                    scored.mark = Some(up);
+                    changed.push(scored.clone());
+                }
+            } else {
+                uneven.push((movement, down, up));
+            }
+        }
+
+        uneven.sort_by(|(a, ..), (b, ..)| {
+            b.coefficient
+                .partial_cmp(&a.coefficient)
+                .unwrap_or(Ordering::Equal)
+                .then(a.number.cmp(&b.number))
+        });
+        for (i, (movement, down, up)) in uneven.into_iter().enumerate() {
+            let mark = if i % 2 == 0 { up } else { down };
+            let Some(scored) = self
+                .scores
+                .iter_mut()
+                .find(|x| x.number == movement.number as u16)
             else {
                 continue;
             };
-            total += exercise.mark.unwrap_or_default() * movement.coefficient;
-            if movement.step.scale() > scale {
-                scale = movement.step.scale();
+            if scored.mark != Some(mark) {
+                scored.mark = Some(mark);
+                changed.push(scored.clone());
             }
-            total.to_precision(scale);
-            // if mark is not none, then count the movement in order to calculate
-            // the running trend
-            max_total += exercise.mark.map_or(dec!(0.0), |_| movement.max) * movement.coefficient;
-            max_total.to_precision(scale);
         }
-        let perc = total.safe_divide(max_total, 6).expect("to divide") * dec!(100);
-        (perc - self.deductions(testsheet)).round_ext(3, decimal::RoundingMode::HalfUp)
+        changed
     }
 }
 
+/// The closest marks below and above the value which fall on the step
+pub(crate) fn step_bounds(value: Decimal, step: Decimal) -> (Decimal, Decimal) {
+    let steps = value.safe_divide(step, 6).unwrap_or_default();
+    (
+        (steps.round_ext(0, RoundingMode::Down) * step).to_precision(step.scale()),
+        (steps.round_ext(0, RoundingMode::Up) * step).to_precision(step.scale()),
+    )
+}
+
 impl crate::traits::Entity for Scoresheet {
     fn key(&self) -> String {
         format!("{}:{}", self.id.tb, self.id.id())
@@ -130,3 +271,263 @@ impl crate::traits::Entity for Scoresheet {
         self.id.id()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use decimal::{dec, Decimal};
+
+    use super::{ScoredMark, Scoresheet};
+    use crate::domain::deductions::PenaltySource;
+    use crate::domain::dressage_test::{DressageTest, Exercise, TestSheetType};
+    use crate::domain::penalties::{Penalties, Penalty, PenaltyType};
+    use crate::domain::SurrealId;
+
+    fn movement(number: u8, coefficient: f32, category: &str) -> Exercise {
+        serde_json::from_value(serde_json::json!({
+            "nr": number,
+            "co": coefficient,
+            "ct": category,
+        }))
+        .expect("A valid movement")
+    }
+
+    fn test_of(movements: Vec<Exercise>) -> DressageTest {
+        DressageTest {
+            movements,
+            test_type: TestSheetType::Freestyle,
+            ..Default::default()
+        }
+    }
+
+    fn sheet(scores: Vec<ScoredMark>) -> Scoresheet {
+        Scoresheet {
+            id: SurrealId::make("scoresheet", "test"),
+            score: None,
+            rank: None,
+            errors: 0,
+            tech_penalties: 0,
+            art_penalties: 0,
+            scores,
+            summary: None,
+            notes: None,
+            warning_manager: Default::default(),
+            locked: false,
+            test: None,
+            ground_jury_member: None,
+            history: Default::default(),
+            amendments: vec![],
+        }
+    }
+
+    fn attempted(number: u16, attempts: &[Decimal]) -> ScoredMark {
+        ScoredMark {
+            attempts: attempts.to_vec(),
+            ..ScoredMark::new(number)
+        }
+    }
+
+    fn marked(number: u16, mark: Decimal) -> ScoredMark {
+        ScoredMark {
+            mark: Some(mark),
+            ..ScoredMark::new(number)
+        }
+    }
+
+    /// The trend and the final score, as the judge would see them
+    fn trend_and_score(test: &DressageTest, sheet: &Scoresheet) -> (String, String) {
+        (
+            test.precision.format(sheet.calculate_trend(test)),
+            test.precision.format(sheet.calculate_score(test)),
+        )
+    }
+
+    fn mark_of(sheet: &Scoresheet, number: u16) -> Option<Decimal> {
+        sheet
+            .scores
+            .iter()
+            .find(|x| x.number == number)
+            .and_then(|x| x.mark)
+    }
+
+    /// Averages to 7.25, halfway between two marks on the 0.5 step
+    fn uneven() -> [Decimal; 2] {
+        [dec!(7.0), dec!(7.5)]
+    }
+
+    #[test]
+    fn odd_count_of_uneven_averages_rounds_up_first_and_last() {
+        let test = test_of((1..=3).map(|x| movement(x, 1.0, "T")).collect());
+        let mut sheet = sheet((1..=3).map(|x| attempted(x, &uneven())).collect());
+        sheet.fair_round_attempts(&test);
+        assert_eq!(mark_of(&sheet, 1), Some(dec!(7.5)));
+        assert_eq!(mark_of(&sheet, 2), Some(dec!(7.0)));
+        assert_eq!(mark_of(&sheet, 3), Some(dec!(7.5)));
+    }
+
+    #[test]
+    fn even_count_of_uneven_averages_rounds_up_as_often_as_down() {
+        let test = test_of((1..=4).map(|x| movement(x, 1.0, "T")).collect());
+        let mut sheet = sheet((1..=4).map(|x| attempted(x, &uneven())).collect());
+        sheet.fair_round_attempts(&test);
+        assert_eq!(mark_of(&sheet, 1), Some(dec!(7.5)));
+        assert_eq!(mark_of(&sheet, 2), Some(dec!(7.0)));
+        assert_eq!(mark_of(&sheet, 3), Some(dec!(7.5)));
+        assert_eq!(mark_of(&sheet, 4), Some(dec!(7.0)));
+    }
+
+    #[test]
+    fn even_averages_are_left_out_of_the_alternation() {
+        let test = test_of((1..=3).map(|x| movement(x, 1.0, "T")).collect());
+        let mut sheet = sheet(vec![
+            attempted(1, &uneven()),
+            attempted(2, &[dec!(7.0), dec!(8.0)]),
+            attempted(3, &uneven()),
+        ]);
+        sheet.fair_round_attempts(&test);
+        assert_eq!(mark_of(&sheet, 1), Some(dec!(7.5)));
+        assert_eq!(mark_of(&sheet, 2), Some(dec!(7.5)));
+        assert_eq!(mark_of(&sheet, 3), Some(dec!(7.0)));
+    }
+
+    #[test]
+    fn higher_coefficients_round_first_and_ties_go_by_movement_number() {
+        let test = test_of(vec![
+            movement(1, 1.0, "T"),
+            movement(2, 2.0, "T"),
+            movement(3, 2.0, "T"),
+        ]);
+        let mut sheet = sheet((1..=3).map(|x| attempted(x, &uneven())).collect());
+        sheet.fair_round_attempts(&test);
+        assert_eq!(mark_of(&sheet, 2), Some(dec!(7.5)));
+        assert_eq!(mark_of(&sheet, 3), Some(dec!(7.0)));
+        assert_eq!(mark_of(&sheet, 1), Some(dec!(7.5)));
+    }
+
+    #[test]
+    fn manually_set_marks_are_left_alone() {
+        let test = test_of((1..=3).map(|x| movement(x, 1.0, "T")).collect());
+        let manual = ScoredMark {
+            mark: Some(dec!(9.0)),
+            manually_set: true,
+            ..attempted(1, &uneven())
+        };
+        let mut sheet = sheet(vec![
+            manual,
+            attempted(2, &uneven()),
+            attempted(3, &uneven()),
+        ]);
+        let changed = sheet.fair_round_attempts(&test);
+        assert_eq!(mark_of(&sheet, 1), Some(dec!(9.0)));
+        assert!(changed.iter().all(|x| x.number != 1));
+        // the alternation starts again from the first movement left to round
+        assert_eq!(mark_of(&sheet, 2), Some(dec!(7.5)));
+        assert_eq!(mark_of(&sheet, 3), Some(dec!(7.0)));
+    }
+
+    #[test]
+    fn rounding_again_only_reports_marks_it_moved() {
+        let test = test_of((1..=2).map(|x| movement(x, 1.0, "T")).collect());
+        let mut sheet = sheet((1..=2).map(|x| attempted(x, &uneven())).collect());
+        assert_eq!(sheet.fair_round_attempts(&test).len(), 2);
+        assert!(sheet.fair_round_attempts(&test).is_empty());
+    }
+
+    #[test]
+    fn particles_are_left_out_of_the_maximum_trend_and_score() {
+        let test = test_of(vec![movement(1, 1.0, "T"), movement(2, 5.0, "P")]);
+        assert_eq!(test.precision.format(test.total_marks()), "10.000");
+        let sheet = sheet(vec![marked(1, dec!(8.0)), marked(2, dec!(10.0))]);
+        assert_eq!(
+            trend_and_score(&test, &sheet),
+            ("80.000".to_string(), "80.000".to_string())
+        );
+    }
+
+    #[test]
+    fn jokers_only_count_once_marked() {
+        let test = test_of(vec![movement(1, 1.0, "T"), movement(2, 1.0, "J")]);
+        assert_eq!(test.precision.format(test.total_marks()), "10.000");
+        let unmarked = sheet(vec![marked(1, dec!(8.0))]);
+        assert_eq!(
+            trend_and_score(&test, &unmarked),
+            ("80.000".to_string(), "80.000".to_string())
+        );
+        let played = sheet(vec![marked(1, dec!(8.0)), marked(2, dec!(6.0))]);
+        assert_eq!(
+            trend_and_score(&test, &played),
+            ("70.000".to_string(), "70.000".to_string())
+        );
+    }
+
+    #[test]
+    fn acceptables_count_against_the_score_even_when_unmarked() {
+        let test = test_of(vec![movement(1, 1.0, "T"), movement(2, 1.0, "E")]);
+        assert_eq!(test.precision.format(test.total_marks()), "20.000");
+        let sheet = sheet(vec![marked(1, dec!(8.0))]);
+        // the trend only counts what has been marked so far
+        assert_eq!(
+            trend_and_score(&test, &sheet),
+            ("80.000".to_string(), "40.000".to_string())
+        );
+    }
+
+    #[test]
+    fn technical_and_artistic_movements_are_counted_normally() {
+        let test = test_of(vec![movement(1, 1.0, "T"), movement(2, 1.0, "A")]);
+        assert_eq!(test.precision.format(test.total_marks()), "20.000");
+        let partial = sheet(vec![marked(1, dec!(8.0))]);
+        assert_eq!(
+            trend_and_score(&test, &partial),
+            ("80.000".to_string(), "40.000".to_string())
+        );
+        let complete = sheet(vec![marked(1, dec!(8.0)), marked(2, dec!(6.0))]);
+        assert_eq!(
+            trend_and_score(&test, &complete),
+            ("70.000".to_string(), "70.000".to_string())
+        );
+    }
+
+    #[test]
+    fn collectives_use_their_coefficient() {
+        let test = test_of(vec![movement(1, 1.0, "T"), movement(2, 2.0, "C")]);
+        assert_eq!(test.precision.format(test.total_marks()), "30.000");
+        let sheet = sheet(vec![marked(1, dec!(6.0)), marked(2, dec!(9.0))]);
+        // at a coefficient of 1 this would be 75%
+        assert_eq!(
+            trend_and_score(&test, &sheet),
+            ("80.000".to_string(), "80.000".to_string())
+        );
+    }
+
+    #[test]
+    fn the_report_total_is_the_deduction_taken_off_the_trend() {
+        let test = DressageTest {
+            technical_penalties: Penalties(vec![Penalty {
+                idx: 0,
+                ty: PenaltyType::Points(dec!(2.0)),
+            }]),
+            ..test_of(vec![movement(1, 20.0, "T"), movement(2, 10.0, "A")])
+        };
+        let clean = sheet(vec![marked(1, dec!(7.0)), marked(2, dec!(8.0))]);
+        // each penalty is two points of three hundred, which is 0.6667%
+        let penalised = Scoresheet {
+            tech_penalties: 3,
+            ..clean.clone()
+        };
+
+        let report = penalised.deduction_report(&test);
+        assert_eq!(report.precision.format(report.total()), "2.000");
+        assert_eq!(
+            test.precision
+                .format(clean.calculate_trend(&test) - penalised.calculate_trend(&test)),
+            report.precision.format(report.total())
+        );
+
+        let breakdown = penalised.trend_breakdown(&test).expect("A freestyle");
+        assert_eq!(
+            breakdown.technical_deduction,
+            report.source_total(PenaltySource::TechnicalPenalties)
+        );
+        assert_eq!(breakdown.total, penalised.calculate_trend(&test));
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     traits::{Entity, Fetchable},
 };
 
-use super::{competition::Competition, SurrealId};
+use super::{competition::Competition, dressage_test::TestDiagnostic, SurrealId};
 
 const API_URL: &str = env!("API_URL");
 
@@ -26,6 +26,14 @@ impl crate::traits::Entity for Show {
         self.id.id()
     }
 }
+impl Show {
+    pub fn diagnostics(&self) -> Vec<TestDiagnostic> {
+        self.competitions
+            .iter()
+            .flat_map(|c| c.diagnostics())
+            .collect()
+    }
+}
 impl Fetchable for Show {
     async fn fetch(
         state: &tauri::State<'_, ManagedApplicationState>,
@@ -49,6 +57,9 @@ impl Fetchable for Show {
             .json::<Vec<Self>>()
             .await
             .inspect_err(|err| eprintln!("{err:?}"))?;
+        for diagnostic in shows.iter().flat_map(|s| s.diagnostics()) {
+            debug!(yellow, "{diagnostic}");
+        }
 
         Ok(shows)
     }
@@ -67,6 +78,9 @@ impl Fetchable for Show {
             .json()
             .await
             .inspect_err(|err| debug!("Decode -> {err:?}"))?;
+        for diagnostic in show.diagnostics() {
+            debug!(yellow, "{diagnostic}");
+        }
         Ok(show)
     }
 }
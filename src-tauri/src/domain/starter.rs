@@ -2,7 +2,7 @@ use decimal::Decimal;
 
 use crate::commands::alert_manager::Alert;
 
-use super::{competitor::Competitor, scoresheet::Scoresheet, SurrealId};
+use super::{competitor::Competitor, dressage_test::Precision, scoresheet::Scoresheet, SurrealId};
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -24,18 +24,18 @@ pub struct Starter {
 }
 
 impl Starter {
-    pub fn score_or_number(&self) -> String {
+    pub fn score_or_number(&self, precision: &Precision) -> String {
         match self.status {
             StarterResult::Upcoming => self.number.to_string(),
             StarterResult::InProgress(_) => {
                 if let Some(scoresheet) = self.scoresheets.first() {
-                    format!("{:.3}", scoresheet.score.unwrap_or_default())
+                    precision.format(scoresheet.score.unwrap_or_default())
                 } else {
-                    format!("{:.3}", self.score.unwrap_or_default())
+                    precision.format(self.score.unwrap_or_default())
                 }
             }
             StarterResult::Placed(_) | StarterResult::NotPlaced(_) => {
-                format!("{:.3}", self.score.unwrap_or_default())
+                precision.format(self.score.unwrap_or_default())
             }
             StarterResult::Eliminated(_) => "Elim".to_string(),
             StarterResult::Withdrawn => "Wdn".to_string(),
@@ -44,8 +44,17 @@ impl Starter {
             StarterResult::Disqualified => "Dsq".to_string(),
         }
     }
-    pub fn time_or_rank(&self) -> String {
+    /// The server's rank, or the provisional rank worked out on the device
+    /// when the server has not given one.
+    pub fn time_or_rank(&self, provisional: Option<u16>) -> String {
         match self.status {
+            StarterResult::InProgress(0)
+            | StarterResult::Placed(0)
+            | StarterResult::NotPlaced(0)
+                if provisional.is_some() =>
+            {
+                format!("Prov. {}", provisional.unwrap_or_default())
+            }
             StarterResult::InProgress(r) => format!("Trend {r}"),
             StarterResult::Placed(r) | StarterResult::NotPlaced(r) => format!("Rk {r}"),
             StarterResult::Upcoming => self.start_time.format("%H:%M").to_string(),
@@ -75,6 +84,63 @@ impl Starter {
     pub fn matches_id(&self, other_id: &SurrealId) -> bool {
         self.id == *other_id
     }
+    /// Takes the details of the starter sent by the server, returning what
+    /// changed. The judge's own scoresheet is only ever changed on this
+    /// device, so it is kept along with the status once marking has begun.
+    pub fn merge(&mut self, incoming: Starter) -> Vec<String> {
+        let mut changes = vec![];
+        if self.name() != incoming.name() {
+            changes.push(format!("Athlete is now {}", incoming.name()));
+        }
+        if self.horse() != incoming.horse() {
+            changes.push(format!("Horse is now {}", incoming.horse()));
+        }
+        if self.competitor.comp_no != incoming.competitor.comp_no {
+            changes.push(format!("Number is now {}", incoming.competitor.comp_no));
+        }
+        if self.start_time != incoming.start_time {
+            changes.push(format!(
+                "Start time is now {}",
+                incoming.start_time.format("%H:%M")
+            ));
+        }
+
+        let own = self.scoresheets.first().cloned();
+        let marking = own
+            .as_ref()
+            .is_some_and(|x| x.locked || !x.scores.is_empty());
+        if !marking && self.status != incoming.status {
+            changes.push(format!(
+                "Status is now {}",
+                incoming.status.list_abbreviation()
+            ));
+            self.status = incoming.status;
+            self.rebase_history();
+        }
+        self.competitor = incoming.competitor;
+        self.start_time = incoming.start_time;
+        self.number = incoming.number;
+        self.index = incoming.index;
+        self.score = incoming.score.or(self.score);
+
+        match own {
+            Some(own) => {
+                let kept = self.scoresheets.drain(1..).collect::<Vec<_>>();
+                let own_id = own.id.clone();
+                let mut sheets = vec![own];
+                sheets.extend(incoming.scoresheets.into_iter().filter(|x| x.id != own_id));
+                // sheets of the panel the server left out are kept as they were
+                for sheet in kept {
+                    if !sheets.iter().any(|x| x.id == sheet.id) {
+                        sheets.push(sheet);
+                    }
+                }
+                self.scoresheets = sheets;
+            }
+            None => self.scoresheets = incoming.scoresheets,
+        }
+        changes
+    }
 }
 
 impl crate::traits::Entity for Starter {
@@ -134,6 +200,15 @@ impl StarterResult {
         }
     }
 
+    /// Whether the starter can be given a placing, which rules out anyone
+    /// who has not started or did not finish.
+    pub fn is_rankable(&self) -> bool {
+        matches!(
+            self,
+            StarterResult::InProgress(_) | StarterResult::Placed(_) | StarterResult::NotPlaced(_)
+        )
+    }
+
     pub fn is_finished(&self) -> bool {
         match self {
             StarterResult::Upcoming | StarterResult::InProgress(_) => false,
@@ -25,6 +25,8 @@ pub fn run() {
         .setup(setup_application_state)
         .manage(Timer::default())
         .manage(AlertManager::new())
+        .manage(sockets::connection::ManagedConnection::default())
+        .manage(sockets::subscriptions::ManagedSubscriptions::default())
         .invoke_handler({
             use commands::*;
             tauri::generate_handler![
@@ -65,6 +67,12 @@ pub fn run() {
                 warnings::status::change_competitor_status,
                 choose_starter::choose_starter,
                 scoresheet::confirm_marks::confirm_marks,
+                history::undo,
+                history::redo,
+                history::show_history,
+                history::export_history,
+                amendment::show_amendment_form,
+                amendment::request_amendment,
                 scoresheet::start_list_bar::filter_starters,
                 bell_timer::ring_bell,
                 bell_timer::start_normal_time,
@@ -87,6 +95,7 @@ fn setup_application_state(app: &mut tauri::App) -> Result<(), Box<dyn std::erro
     let app_handle = app.handle();
     // set up internal state, retriving previous session from memory
     ManagedApplicationState::initialize(app_handle.clone());
+    app.manage(sockets::outbox::ManagedOutbox::load(app_handle));
 
     #[cfg(debug_assertions)] // Open dev tray in development build
     app.get_webview_window("main").unwrap().open_devtools();
@@ -0,0 +1,98 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::Manager;
+
+use crate::commands::replace_director::{emit_page, PageLocation};
+use crate::debug;
+use crate::templates::scoresheet::connection::connection_indicator;
+
+use super::outbox::ManagedOutbox;
+
+const FIRST_DELAY: Duration = Duration::from_secs(1);
+const LONGEST_DELAY: Duration = Duration::from_secs(60);
+
+/// How the judge's connection to the server is faring, shown in the
+/// header of the scoresheet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConnectionState {
+    #[default]
+    Connecting,
+    Online,
+    /// Connected, but the server is not acknowledging what it is sent
+    Degraded,
+    Offline,
+    /// The token could not be refreshed, so the judge must log in again
+    AuthFailed,
+}
+
+impl ConnectionState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Connecting => "Connecting",
+            Self::Online => "Online",
+            Self::Degraded => "Degraded",
+            Self::Offline => "Offline",
+            Self::AuthFailed => "Log in again",
+        }
+    }
+}
+
+/// The wait between attempts to connect, doubling with each failure
+#[derive(Debug, Default)]
+pub struct Backoff {
+    failures: u32,
+}
+
+impl Backoff {
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+
+    /// Half of the wait is random, so that the tablets of a whole jury
+    /// do not all reconnect at the same moment when the server returns
+    pub fn next_delay(&mut self) -> Duration {
+        let ceiling = FIRST_DELAY
+            .saturating_mul(2u32.saturating_pow(self.failures.min(16)))
+            .min(LONGEST_DELAY);
+        self.failures = self.failures.saturating_add(1);
+        let half = ceiling / 2;
+        half + Duration::from_millis(rand::random_range(0..=half.as_millis() as u64))
+    }
+}
+
+#[derive(Default)]
+pub struct ManagedConnection(Mutex<ConnectionState>);
+
+impl ManagedConnection {
+    pub fn get(&self) -> ConnectionState {
+        self.0.lock().map_or(ConnectionState::Offline, |x| *x)
+    }
+
+    /// Records the state of the connection, updating the indicator when
+    /// it has changed
+    pub fn set(&self, handle: &tauri::AppHandle, state: ConnectionState) {
+        let Ok(mut current) = self.0.lock() else {
+            return;
+        };
+        if *current == state {
+            return;
+        }
+        debug!(dim, "Connection {:?} -> {:?}", *current, state);
+        *current = state;
+        drop(current);
+        emit_connection(handle);
+    }
+}
+
+/// Updates the connection indicator, such as after the number of messages
+/// waiting on the server has changed
+pub fn emit_connection(handle: &tauri::AppHandle) {
+    let state = handle.state::<ManagedConnection>().get();
+    let unsent = handle.state::<ManagedOutbox>().len();
+    emit_page(
+        handle,
+        &PageLocation::ConnectionIndicator,
+        connection_indicator(state, unsent),
+    );
+}
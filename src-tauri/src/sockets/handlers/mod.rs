@@ -5,8 +5,11 @@ use tauri_plugin_store::StoreExt;
 
 use super::message_types::server::Payload;
 use super::message_types::{common, server};
+use super::outbox::ManagedOutbox;
+use super::subscriptions::ManagedSubscriptions;
 use crate::commands::replace_director::{PageLocation, ReplaceDirector};
-use crate::domain::starter::StarterResult;
+use crate::domain::position::Position;
+use crate::domain::SurrealId;
 use crate::state::{ApplicationState, ManagedApplicationState};
 use crate::{debug, STATE};
 
@@ -16,40 +19,74 @@ impl server::Trend {
         use crate::templates::scoresheet::header_trend;
 
         let state = handle.state::<ManagedApplicationState>();
-        state
+        let (breakdown, precision) = state
             .write(|app_state| {
                 if let Some(starter) = app_state.starter_mut() {
                     starter.impose_trend(&self);
                 }
+                let Some(test) = app_state.get_test() else {
+                    return (None, Default::default());
+                };
+                let breakdown = app_state.scoresheet().and_then(|x| x.trend_breakdown(test));
+                (breakdown, test.precision)
             })
             .map_err(|_| FatalHandlerError::StateMissing)?;
-        let header = header_trend(Some(self.score), Some(self.rank), false);
+        let header = header_trend(
+            Some(self.score),
+            Some(self.rank),
+            false,
+            breakdown,
+            precision,
+        );
         let trend = hypertext::Renderable::render(&header);
         emit_page_prerendered(&handle, &PageLocation::HeaderTrend, trend.clone());
         emit_page_prerendered(&handle, &PageLocation::TotalScore, trend);
-        Ok(())
+        emit_deviations(&handle)
     }
 }
 
 impl server::Reset {
     pub fn handle(self, handle: tauri::AppHandle) -> HandlerResult {
+        use crate::commands::alert_manager::AlertManager;
+        use crate::commands::bell_timer::Timer;
+        use crate::commands::replace_director::{emit_page, emit_page_with_director};
+        use crate::templates::scoresheet::{reset_notice, scoresheet};
+
         const THIRTY_SECONDS: chrono::Duration = chrono::Duration::seconds(30);
-        if self.timestamp > chrono::Utc::now() - THIRTY_SECONDS {
-            let state = handle.state::<ManagedApplicationState>();
-            let _ = state.write(|app_state| {
+        if self.timestamp <= chrono::Utc::now() - THIRTY_SECONDS {
+            return Ok(());
+        }
+        let state = handle.state::<ManagedApplicationState>();
+        let on_screen = state
+            .write(|app_state| {
+                let on_screen = app_state
+                    .starter()
+                    .is_some_and(|x| x.matches_sheet_ulid(&self.sheet_id));
                 if let Some(starter) = app_state.starter_from_sheet_ulid_mut(&self.sheet_id) {
-                    if let Some(scoresheet) = starter.scoresheets.first_mut() {
-                        scoresheet.scores = vec![];
-                        scoresheet.score = None;
-                        starter.score = None;
-                        starter.status = StarterResult::Upcoming;
-                        scoresheet.errors = 0;
-                        scoresheet.tech_penalties = 0;
-                        scoresheet.art_penalties = 0;
-                    }
+                    starter.impose_reset();
                 }
+                if on_screen {
+                    // marks still being typed belong to the sheet which was reset
+                    app_state.score_debounces.cancel_all();
+                }
+                on_screen
+            })
+            .map_err(FatalHandlerError::from)?;
+        auto_state_saver::<ApplicationState>(&handle, STATE, |app_state| {
+            if let Some(starter) = app_state.starter_from_sheet_ulid_mut(&self.sheet_id) {
+                starter.impose_reset();
+            }
+            Ok(())
+        })?;
+
+        if on_screen {
+            handle.state::<AlertManager>().clear();
+            handle.state::<Timer>().stop();
+            tauri::async_runtime::spawn(async move {
+                let page = scoresheet(handle.state(), handle.state(), handle.clone()).await;
+                emit_page_with_director(&handle, page.unwrap_or_else(|x| x));
+                emit_page(&handle, &PageLocation::StarterNotice, reset_notice());
             });
-            // TODO: Clear all
         }
         Ok(())
     }
@@ -57,22 +94,78 @@ impl server::Reset {
 
 impl server::AlterStarter {
     pub fn handle(self, handle: tauri::AppHandle) -> HandlerResult {
-        Ok(())
+        use crate::commands::replace_director::{emit_page, emit_page_prerendered};
+        use crate::templates::scoresheet::start_list_bar::get_starters_list;
+        use crate::templates::scoresheet::{starter_names, starter_notice};
+
+        let state = handle.state::<ManagedApplicationState>();
+        // writing the state also writes the show through to the store
+        let rendered = state
+            .write(|app_state| {
+                let current = app_state.starter().map(|x| x.id.clone());
+                let changes = app_state
+                    .competition_mut()?
+                    .upsert_starters(self.starters)
+                    .into_iter()
+                    .find(|(id, _)| current.as_ref() == Some(id))
+                    .map(|(_, changes)| changes)
+                    .unwrap_or_default();
+
+                let competition = app_state.competition()?;
+                let starter = app_state.starter()?;
+                let judge = competition.jury.first()?;
+                let ranking = competition.ranking();
+                let list = get_starters_list(competition, &starter.id, judge, &ranking, None);
+                Some((
+                    hypertext::Renderable::render(&list),
+                    hypertext::Renderable::render(&starter_names(starter)),
+                    changes,
+                ))
+            })
+            .map_err(FatalHandlerError::from)?;
+
+        // not on a scoresheet, so there is nothing on screen to update
+        let Some((list, names, changes)) = rendered else {
+            return Ok(());
+        };
+        emit_page_prerendered(&handle, &PageLocation::StartersList, list);
+        emit_page_prerendered(&handle, &PageLocation::StarterNames, names);
+        if !changes.is_empty() {
+            emit_page(
+                &handle,
+                &PageLocation::StarterNotice,
+                starter_notice(changes),
+            );
+        }
+        emit_deviations(&handle)
     }
 }
 
 impl server::Lock {
     pub(in crate::sockets) fn handle(self, handle: tauri::AppHandle) -> HandlerResult {
+        use crate::commands::replace_director::emit_page;
+        use crate::domain::dressage_test::TestSheetType;
+        use crate::templates::scoresheet::history::history_controls;
+        use crate::templates::scoresheet::{
+            artistic_row, attempt_cell, deduction_report, errors_row, get_confirm_or_signature,
+            mark_cell, remark_cell, technical_row, zip_exercise_and_marks,
+        };
+
         let state = handle.state::<ManagedApplicationState>();
-        state
+        let imposed = state
             .write(|app_state| {
-                if let Some(ref mut starter) = app_state.starter_mut() {
-                    if starter.matches_sheet_ulid(&self.sheet_id) {
-                        starter.impose_lock(&self);
-
-                        // TODO: Emit to screen
-                    }
+                let signature = app_state
+                    .competition()
+                    .and_then(|x| x.jury.first())
+                    .and_then(|x| x.judge.signature.clone());
+                let starter = app_state.starter_mut()?;
+                if !starter.matches_sheet_ulid(&self.sheet_id) {
+                    return None;
                 }
+                let corrections = starter.impose_lock(&self);
+                let test = app_state.get_test().cloned()?;
+                let scoresheet = app_state.scoresheet()?.clone();
+                Some((test, scoresheet, corrections, signature))
             })
             .map_err(FatalHandlerError::from)?;
         auto_state_saver::<ApplicationState>(&handle, STATE, |app_state| {
@@ -83,26 +176,317 @@ impl server::Lock {
             }
             Ok(())
         })?;
+
+        // the lock may be for a starter the judge has moved on from
+        let Some((test, scoresheet, corrections, signature)) = imposed else {
+            return Ok(());
+        };
+        let amendable = scoresheet.amendable();
+        let is_freestyle_mode = test.test_type == TestSheetType::Freestyle;
+        let report = scoresheet.deduction_report(&test);
+        for (exercise, mark) in
+            zip_exercise_and_marks(test.movements.clone(), scoresheet.scores.clone())
+        {
+            let number = exercise.number as u16;
+            let locked = scoresheet.locked && !amendable.contains(&number);
+            emit_page(
+                &handle,
+                &PageLocation::Any(format!(
+                    "tr[data-index='{}'] td.exercise-mark",
+                    exercise.number
+                )),
+                mark_cell(
+                    &mark,
+                    &exercise,
+                    locked,
+                    corrections.iter().find(|x| x.number == number),
+                ),
+            );
+            emit_page(
+                &handle,
+                &PageLocation::Any(format!(
+                    "tr[data-index='{}'] td.exercise-remark",
+                    exercise.number
+                )),
+                remark_cell(
+                    exercise.number,
+                    mark.as_ref().and_then(|x| x.remark.clone()),
+                    locked,
+                ),
+            );
+            if is_freestyle_mode && exercise.category.has_attempts() {
+                emit_page(
+                    &handle,
+                    &PageLocation::Any(format!(
+                        "tr[data-index='{}'] td.exercise-attempt",
+                        exercise.number
+                    )),
+                    attempt_cell(
+                        exercise.number,
+                        mark.as_ref().map_or(0, |x| x.attempts.len()),
+                        scoresheet.locked,
+                    ),
+                );
+            }
+        }
+        emit_page(
+            &handle,
+            &PageLocation::PenaltiesErrors,
+            errors_row(!test.errors_of_course.is_empty(), scoresheet.errors),
+        );
+        emit_page(
+            &handle,
+            &PageLocation::PenaltiesTechnical,
+            technical_row(
+                !test.technical_penalties.is_empty(),
+                scoresheet.tech_penalties,
+            ),
+        );
+        emit_page(
+            &handle,
+            &PageLocation::PenaltiesArtistic,
+            artistic_row(
+                !test.artistic_penalties.is_empty(),
+                scoresheet.art_penalties,
+            ),
+        );
+        emit_page(&handle, &PageLocation::Deductions, deduction_report(report));
+        emit_page(
+            &handle,
+            &PageLocation::HistoryControls,
+            history_controls(&scoresheet.history, scoresheet.locked),
+        );
+        emit_page(
+            &handle,
+            &PageLocation::ConfirmMarks,
+            get_confirm_or_signature(scoresheet.locked, !amendable.is_empty(), signature),
+        );
+        emit_deviations(&handle)
+    }
+}
+
+impl server::AmendmentDecision {
+    pub fn handle(self, handle: tauri::AppHandle) -> HandlerResult {
+        use crate::commands::replace_director::{emit_page, emit_page_outer};
+        use crate::templates::scoresheet::amendment::amendment_status;
+        use crate::templates::scoresheet::{
+            get_confirm_or_signature, get_main_mark_input, zip_exercise_and_marks,
+        };
+
+        let state = handle.state::<ManagedApplicationState>();
+        let decided = state
+            .write(|app_state| {
+                let test = app_state.get_test().cloned()?;
+                let signature = app_state
+                    .competition()
+                    .and_then(|x| x.jury.first())
+                    .and_then(|x| x.judge.signature.clone());
+                let starter = app_state.starter_mut()?;
+                if !starter.matches_sheet_ulid(&self.sheet_id) {
+                    return None;
+                }
+                let scoresheet = starter.scoresheets.first_mut()?;
+                let amendment = scoresheet
+                    .decide_amendment(&self.id, self.approved, self.decided_by.clone())?
+                    .clone();
+                let amendable = scoresheet.amendable();
+                let marks = zip_exercise_and_marks(test.movements, scoresheet.scores.clone());
+                Some((amendment, amendable, marks, signature))
+            })
+            .map_err(FatalHandlerError::from)?;
+        auto_state_saver::<ApplicationState>(&handle, STATE, |app_state| {
+            if let Some(starter) = app_state.starter_from_sheet_ulid_mut(&self.sheet_id) {
+                if let Some(scoresheet) = starter.scoresheets.first_mut() {
+                    scoresheet.decide_amendment(&self.id, self.approved, self.decided_by.clone());
+                }
+            }
+            Ok(())
+        })?;
+
+        // the decision may be for a starter the judge has moved on from
+        let Some((amendment, amendable, marks, signature)) = decided else {
+            return Ok(());
+        };
+        for (exercise, mark) in marks
+            .into_iter()
+            .filter(|(x, _)| amendment.movements.contains(&(x.number as u16)))
+        {
+            emit_page_outer(
+                &handle,
+                &PageLocation::Any(format!(
+                    "tr[data-index='{}'] .exercise-input[data-input-role='mark']",
+                    exercise.number
+                )),
+                get_main_mark_input(
+                    &mark,
+                    &exercise,
+                    !amendable.contains(&(exercise.number as u16)),
+                ),
+            );
+        }
+        emit_page(
+            &handle,
+            &PageLocation::AmendmentAside,
+            amendment_status(Some(&amendment)),
+        );
+        emit_page(
+            &handle,
+            &PageLocation::ConfirmMarks,
+            get_confirm_or_signature(true, !amendable.is_empty(), signature),
+        );
         Ok(())
     }
 }
 
 impl common::Signal {
     pub fn handle(self, handle: tauri::AppHandle) -> HandlerResult {
+        use crate::commands::alert_manager::AlertManager;
+        use crate::commands::replace_director::emit_page;
+        use crate::templates::scoresheet::warnings::get_warnings;
+
+        let state = handle.state::<ManagedApplicationState>();
+        let on_screen = state
+            .write(|app_state| {
+                let (id, position) = sender_of(app_state, &self.sheet_id, self.position.clone())?;
+                // the server echoes the judge's own signal back
+                if app_state.competition()?.get_position().as_ref() == Some(&position) {
+                    return None;
+                }
+                let current = app_state.starter().is_some_and(|x| x.id == id);
+                let starter = app_state
+                    .competition_mut()?
+                    .starters
+                    .iter_mut()
+                    .find(|x| x.id == id)?;
+                starter.impose_signal(&self, &position);
+                current.then_some(position)
+            })
+            .map_err(FatalHandlerError::from)?;
+
+        if let Some(position) = on_screen {
+            let alert_manager = handle.state::<AlertManager>();
+            alert_manager.set(self.signal.clone(), &position, self.active);
+            emit_page(
+                &handle,
+                &PageLocation::AlertsAndWarnings,
+                get_warnings(alert_manager),
+            );
+        }
         Ok(())
     }
 }
 
 impl common::Status {
     pub fn handle(self, handle: tauri::AppHandle) -> HandlerResult {
+        use crate::commands::alert_manager::AlertManager;
+        use crate::commands::replace_director::emit_page;
+        use crate::templates::scoresheet::{status_selection, warnings::get_warnings};
+
+        let state = handle.state::<ManagedApplicationState>();
+        let on_screen = state
+            .write(|app_state| {
+                let (id, position) = sender_of(app_state, &self.sheet_id, self.position.clone())?;
+                // the server echoes the judge's own status back
+                if app_state.competition()?.get_position().as_ref() == Some(&position) {
+                    return None;
+                }
+                let current = app_state.starter().is_some_and(|x| x.id == id);
+                let starter = app_state
+                    .competition_mut()?
+                    .starters
+                    .iter_mut()
+                    .find(|x| x.id == id)?;
+                (starter.impose_status(&self, &position) && current).then_some(position)
+            })
+            .map_err(FatalHandlerError::from)?;
+
+        if let Some(position) = on_screen {
+            let alert_manager = handle.state::<AlertManager>();
+            alert_manager.set_status(&position, &self.status);
+            emit_page(
+                &handle,
+                &PageLocation::StatusSelector,
+                status_selection(self.status.clone()),
+            );
+            emit_page(
+                &handle,
+                &PageLocation::AlertsAndWarnings,
+                get_warnings(alert_manager),
+            );
+        }
         Ok(())
     }
 }
+
+/// Compares the panel of the starter on screen again, as the scoresheets
+/// it is drawn from have changed
+fn emit_deviations(handle: &tauri::AppHandle) -> HandlerResult {
+    use crate::commands::alert_manager::AlertManager;
+    use crate::commands::replace_director::emit_page;
+    use crate::templates::scoresheet::warnings::get_warnings;
+
+    let deviations = handle
+        .state::<ManagedApplicationState>()
+        .read(|app_state| {
+            let competition = app_state.competition()?;
+            Some(app_state.starter()?.deviations(competition))
+        })
+        .map_err(FatalHandlerError::from)?;
+    let Some(deviations) = deviations else {
+        return Ok(());
+    };
+    let alert_manager = handle.state::<AlertManager>();
+    alert_manager.merge_deviations(&deviations);
+    emit_page(
+        handle,
+        &PageLocation::AlertsAndWarnings,
+        get_warnings(alert_manager),
+    );
+    Ok(())
+}
+
+/// Finds the starter in the current competition with the scoresheet of any
+/// judge, along with the position of the judge who marks it
+fn sender_of(
+    app_state: &ApplicationState,
+    sheet_id: &ulid::Ulid,
+    position: Option<Position>,
+) -> Option<(SurrealId, Position)> {
+    let competition = app_state.competition()?;
+    let starter = competition
+        .starters
+        .iter()
+        .find(|x| x.scoresheets.iter().any(|s| s.id.ulid() == *sheet_id))?;
+    let position = position.or_else(|| starter.sheet_position(competition, sheet_id))?;
+    Some((starter.id.clone(), position))
+}
 pub fn handle_ack(ulid: ulid::Ulid, handle: &tauri::AppHandle) {
-    if let Ok(store) = handle.store(env!("STORE_URI")) {
-        // TODO: Fix this so that it remoes the messages properly and doesn't mess with store
-        debug!(dim, "Ack {ulid}");
+    let removed = handle
+        .state::<ManagedOutbox>()
+        .update(handle, |x| x.acknowledge(&ulid));
+    debug!(
+        dim,
+        "Ack {ulid} {}",
+        if removed == Some(true) {
+            ""
+        } else {
+            "(not waiting)"
+        }
+    );
+    if removed == Some(true) {
+        super::connection::emit_connection(handle);
+    }
+}
+/// The server has stopped sending this competition, so it is no longer
+/// subscribed to after reconnecting, and the judge is told
+pub fn handle_unsubscribe(handle: &tauri::AppHandle) -> HandlerResult {
+    use crate::commands::replace_director::emit_page;
+    use crate::templates::scoresheet::unsubscribed_notice;
+
+    if handle.state::<ManagedSubscriptions>().forget() {
+        emit_page(handle, &PageLocation::StarterNotice, unsubscribed_notice());
     }
+    Ok(())
 }
 pub fn handle_application_state(a: Payload) {
     debug!(green, "App State {a:?}");
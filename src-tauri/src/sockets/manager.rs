@@ -5,16 +5,21 @@ use crate::debug;
 use crate::sockets::handlers::{self, handle_ack, handle_application_state};
 use crate::sockets::message_types::server::Payload;
 use crate::sockets::message_types::{application, server};
-use crate::state::ManagedApplicationState;
+use crate::state::{ManagedApplicationState, StatefulRequestError};
 use socket_manager::SocketError;
 use socket_manager::{message::Message, SocketManager};
 use tauri::Manager;
-use tauri_plugin_store::StoreExt;
 
+use super::connection::{emit_connection, Backoff, ConnectionState, ManagedConnection};
 use super::handlers::HandlerError;
+use super::outbox::ManagedOutbox;
+use super::subscriptions::ManagedSubscriptions;
 
-pub const STORED_MESSAGES: &str = "STORED_MESSAGES";
 const DURATION: std::time::Duration = std::time::Duration::from_secs(10);
+/// Limits on a single batch frame, so a long time offline doesn't produce
+/// a frame the server refuses
+const BATCH_MESSAGES: usize = 50;
+const BATCH_BYTES: usize = 64 * 1024;
 
 pub struct ManagedSocket(
     pub Arc<RwLock<SocketManager<tauri::AppHandle, application::Payload, server::Payload>>>,
@@ -43,14 +48,24 @@ pub async fn manage(owned_handle: tauri::AppHandle) {
         handle.clone(),
     );
     let state = handle.state::<ManagedApplicationState>();
+    let connection = handle.state::<ManagedConnection>();
+    let mut backoff = Backoff::default();
     loop {
-        if state.refresh_if_required().await.is_err() {
+        connection.set(&handle, ConnectionState::Connecting);
+        if let Err(err) = state.refresh_if_required().await {
+            debug!(red, "{err:?}");
+            connection.set(&handle, refresh_failure(&err));
+            tokio::time::sleep(backoff.next_delay()).await;
             continue;
         };
         let mut built_manager = builder.clone();
         let url = match get_url_with_query_token(&handle).await {
             Some(url) => url,
-            None => continue,
+            None => {
+                connection.set(&handle, ConnectionState::AuthFailed);
+                tokio::time::sleep(backoff.next_delay()).await;
+                continue;
+            }
         };
         match built_manager.connect_and_run(&url) {
             Ok((sender, manager)) => {
@@ -61,11 +76,37 @@ pub async fn manage(owned_handle: tauri::AppHandle) {
                 } else {
                     owned_handle.manage(ManagedSocket::new(sender));
                 }
+                backoff.reset();
+                connection.set(&handle, ConnectionState::Online);
+                let subscribed = owned_handle
+                    .state::<ManagedSubscriptions>()
+                    .resubscribe(&owned_handle.state::<ManagedSocket>())
+                    .await;
+                if let Err(err) = subscribed {
+                    debug!(red, "{err:?}");
+                }
+                // anything unacknowledged was probably lost with the last connection
+                owned_handle
+                    .state::<ManagedOutbox>()
+                    .update(&owned_handle, |x| x.retry_now());
                 debug!(dim, "Handler managing things");
                 let _ = manager.await.inspect_err(|err| debug!(red, "{err:?}"));
             }
             Err(err) => debug!("{err:?}"),
         }
+        connection.set(&handle, ConnectionState::Offline);
+        tokio::time::sleep(backoff.next_delay()).await;
+    }
+}
+
+/// The server refusing the refresh token means the judge has to log in
+/// again, whereas anything else is most likely the network
+fn refresh_failure(err: &StatefulRequestError) -> ConnectionState {
+    match err {
+        StatefulRequestError::Http(err) if err.status().is_some_and(|x| x.is_client_error()) => {
+            ConnectionState::AuthFailed
+        }
+        _ => ConnectionState::Offline,
     }
 }
 
@@ -115,7 +156,8 @@ async fn recieve_handler(
                 CM::Status(x) => x.handle(handle),
                 CM::Signal(x) => x.handle(handle),
                 CM::AlterStarter(x) => x.handle(handle),
-                CM::Unsubscribe => Err(Box::new(MessageError::ClosedByServer))?,
+                CM::AmendmentDecision(x) => x.handle(handle),
+                CM::Unsubscribe => handlers::handle_unsubscribe(&handle),
             };
             if let Err(x) = response {
                 if let handlers::HandlerError::Fatal(_) = x {
@@ -132,24 +174,14 @@ fn transform_handler(
     msg: application::Payload,
     handle: tauri::AppHandle,
 ) -> socket_manager::tungstenite::Message {
-    // convert to Message if required
-    let original_message = Message::new(msg.clone());
-    // store message in storage
-    {
-        if let Ok(store) = handle.store(env!("STORE_URI")) {
-            let mut prev_messages: Vec<application::Payload> = store
-                .get(STORED_MESSAGES)
-                .and_then(|msg| serde_json::from_value(msg).ok())
-                .unwrap_or_default();
-
-            prev_messages.push(msg);
-            store.set(
-                STORED_MESSAGES,
-                serde_json::to_value(prev_messages).expect("Should be able to parse"),
-            );
-        }
+    let original_message = Message::new(msg);
+    // keep it until the server acknowledges it
+    if original_message.message.needs_ack() {
+        handle
+            .state::<ManagedOutbox>()
+            .update(&handle, |x| x.push(original_message.clone()));
+        emit_connection(&handle);
     }
-    // return message
     original_message.to_msg()
 }
 async fn keep_alive_handler(_: (), handle: tauri::AppHandle) -> Option<application::Payload> {
@@ -158,21 +190,29 @@ async fn keep_alive_handler(_: (), handle: tauri::AppHandle) -> Option<applicati
         .try_state::<ManagedSocket>()
         .expect("To always have this available inside the handler");
 
-    // TODO: Make this into a handler which batches these message into
-    // a vec before sending. Server also needs to be updated to
-    // handle this batch processing.
-    if let Ok(store) = handle.store(env!("STORE_URI")) {
-        let prev_messages: Vec<Message<application::Payload>> = store
-            .get(STORED_MESSAGES)
-            .and_then(|msg| serde_json::from_value(msg).ok())
-            .unwrap_or_default();
+    let connection = handle.state::<ManagedConnection>();
+    let outbox = handle.state::<ManagedOutbox>();
 
-        for pm in prev_messages.clone().into_iter() {
-            if socks.send_raw(pm).await.is_err() {
-                return None;
-            }
+    let due = outbox
+        .update(&handle, |x| x.take_due(chrono::Utc::now()))
+        .unwrap_or_default();
+    for mut batch in batches(due).into_iter() {
+        let sent = match batch.len() {
+            1 => socks.send_raw(batch.remove(0)).await,
+            _ => socks.send(application::Payload::Batch(batch)).await,
+        };
+        if sent.is_err() {
+            connection.set(&handle, ConnectionState::Degraded);
+            return None;
         }
     }
+    connection.set(
+        &handle,
+        match outbox.stalled() {
+            true => ConnectionState::Degraded,
+            false => ConnectionState::Online,
+        },
+    );
     state
         .write(|app_state| {
             app_state.battery.check();
@@ -181,10 +221,32 @@ async fn keep_alive_handler(_: (), handle: tauri::AppHandle) -> Option<applicati
         .ok()?
 }
 
+/// Splits the messages into batches within the limits of a frame, keeping
+/// them in the order they were first sent
+fn batches(
+    messages: Vec<Message<application::Payload>>,
+) -> Vec<Vec<Message<application::Payload>>> {
+    let mut batches: Vec<Vec<Message<application::Payload>>> = vec![];
+    let mut bytes = 0;
+    for message in messages.into_iter() {
+        // measured as MessagePack, the encoding the frames are sent in
+        let size = rmp_serde::to_vec_named(&message).map_or(BATCH_BYTES, |x| x.len());
+        match batches.last_mut() {
+            Some(batch) if batch.len() < BATCH_MESSAGES && bytes + size <= BATCH_BYTES => {
+                bytes += size;
+                batch.push(message);
+            }
+            _ => {
+                bytes = size;
+                batches.push(vec![message]);
+            }
+        }
+    }
+    batches
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum MessageError {
-    #[error("Closed by server")]
-    ClosedByServer,
     #[error(transparent)]
     Socket(#[from] SocketError),
     #[error("Error occured in handler {0}")]
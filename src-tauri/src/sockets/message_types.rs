@@ -2,6 +2,7 @@ pub mod common {
     use ulid::Ulid;
 
     use crate::commands::alert_manager::AlertType;
+    use crate::domain::position::Position;
     use crate::domain::scoresheet::ScoredMark;
     use crate::domain::starter::StarterResult;
 
@@ -10,12 +11,42 @@ pub mod common {
         #[serde(rename = "sid")]
         pub(in crate::sockets) sheet_id: ulid::Ulid,
         pub(in crate::sockets) signal: AlertType,
+        /// Whether the signal is being raised or taken down
+        #[serde(rename = "on", default = "raised")]
+        pub(in crate::sockets) active: bool,
+        /// The position of the judge who sent it, otherwise found from the sheet
+        #[serde(rename = "p", default)]
+        pub(in crate::sockets) position: Option<Position>,
+    }
+    fn raised() -> bool {
+        true
+    }
+    impl Signal {
+        pub fn new(sheet_id: Ulid, signal: AlertType, active: bool, position: Position) -> Self {
+            Self {
+                sheet_id,
+                signal,
+                active,
+                position: Some(position),
+            }
+        }
     }
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub(crate) struct Status {
         #[serde(rename = "sid")]
         pub(in crate::sockets) sheet_id: Ulid,
         pub(in crate::sockets) status: StarterResult,
+        #[serde(rename = "p", default)]
+        pub(in crate::sockets) position: Option<Position>,
+    }
+    impl Status {
+        pub fn new(sheet_id: Ulid, status: StarterResult, position: Position) -> Self {
+            Self {
+                sheet_id,
+                status,
+                position: Some(position),
+            }
+        }
     }
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub(crate) struct Lock {
@@ -30,10 +61,13 @@ pub mod application {
     use ulid::Ulid;
 
     use crate::domain::penalties::BroadcastPenaltyVariety;
+    use crate::domain::position::Position;
     use crate::domain::SurrealId;
     use crate::state::application_page::ApplicationPage;
     use crate::state::battery::VirtualDeviceBattery;
 
+    use socket_manager::message::Message;
+
     use super::common::{Lock, Signal, Status};
 
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -55,6 +89,9 @@ pub mod application {
         },
         NoOp,
         Ack(ulid::Ulid),
+        /// Several queued messages in one frame. Each keeps its own ID, and
+        /// the server acknowledges them one by one.
+        Batch(Vec<Message<Payload>>),
     }
     impl Payload {
         pub fn mark(
@@ -70,6 +107,42 @@ pub mod application {
                 remark,
             }))
         }
+        /// Whether the server has to acknowledge the message, so that it is
+        /// kept and sent again until it does. Acks and the application state
+        /// are sent fresh each time instead, and subscriptions are made
+        /// again on every connection.
+        pub fn needs_ack(&self) -> bool {
+            matches!(self, Self::Competition(x) if !matches!(x, CompetitionMessage::Unsubscribe))
+        }
+        /// Whether this message carries everything of an earlier one, which
+        /// then no longer needs to reach the server
+        pub fn supersedes(&self, earlier: &Self) -> bool {
+            use CompetitionMessage as CM;
+            match (self, earlier) {
+                (Self::Competition(CM::Mark(a)), Self::Competition(CM::Mark(b))) => {
+                    a.sheet_id == b.sheet_id && a.number == b.number
+                }
+                (Self::Competition(CM::Status(a)), Self::Competition(CM::Status(b))) => {
+                    a.sheet_id == b.sheet_id
+                }
+                (Self::Competition(CM::Lock(a)), Self::Competition(CM::Lock(b))) => {
+                    a.sheet_id == b.sheet_id
+                }
+                (Self::Competition(CM::Penalty(a)), Self::Competition(CM::Penalty(b))) => {
+                    a.sheet_id == b.sheet_id && a.variety == b.variety
+                }
+                _ => false,
+            }
+        }
+        /// Whether the message may be dropped to make room in a full outbox.
+        /// Marks and locks are the judge's scores, so they are kept however
+        /// long the server takes to acknowledge them.
+        pub fn evictable(&self) -> bool {
+            !matches!(
+                self,
+                Self::Competition(CompetitionMessage::Mark(_) | CompetitionMessage::Lock(_))
+            )
+        }
     }
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
     #[serde(rename_all = "camelCase")]
@@ -81,6 +154,18 @@ pub mod application {
         Signal(Signal),
         Status(Status),
         Lock(Lock),
+        Amendment(AmendmentRequest),
+    }
+    /// Asks the chief judge or scorer to reopen movements of a locked sheet
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    pub struct AmendmentRequest {
+        #[serde(rename = "sid")]
+        pub sheet_id: Ulid,
+        pub id: Ulid,
+        #[serde(rename = "n")]
+        pub movements: Vec<u16>,
+        #[serde(rename = "r")]
+        pub reason: String,
     }
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub struct Mark {
@@ -118,6 +203,23 @@ pub mod application {
         variety: BroadcastPenaltyVariety,
         #[serde(rename = "q")]
         quantity: u8,
+        #[serde(rename = "p")]
+        position: Position,
+    }
+    impl Penalty {
+        pub fn new(
+            sheet_id: Ulid,
+            variety: BroadcastPenaltyVariety,
+            quantity: u8,
+            position: Position,
+        ) -> Self {
+            Self {
+                sheet_id,
+                variety,
+                quantity,
+                position,
+            }
+        }
     }
 }
 pub mod server {
@@ -172,6 +274,7 @@ pub mod server {
         AlterStarter(AlterStarter),
         Status(Status),
         Lock(Lock),
+        AmendmentDecision(AmendmentDecision),
     }
 
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -189,7 +292,26 @@ pub mod server {
     }
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub(in crate::sockets) struct AlterStarter {
-        starter: Starter,
+        /// Older servers send a single `starter` rather than the list
+        #[serde(alias = "starter", deserialize_with = "one_or_many")]
+        pub starters: Vec<Starter>,
+    }
+    fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<Starter>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::Deserialize;
+
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum OneOrMany {
+            Many(Vec<Starter>),
+            One(Box<Starter>),
+        }
+        Ok(match OneOrMany::deserialize(deserializer)? {
+            OneOrMany::Many(starters) => starters,
+            OneOrMany::One(starter) => vec![*starter],
+        })
     }
 
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -201,6 +323,18 @@ pub mod server {
         pub(in crate::sockets) timestamp: chrono::DateTime<chrono::Utc>,
     }
 
+    /// The chief judge or scorer's answer to an amendment request
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    pub(in crate::sockets) struct AmendmentDecision {
+        #[serde(rename = "sid")]
+        pub sheet_id: Ulid,
+        pub id: Ulid,
+        #[serde(rename = "ok")]
+        pub approved: bool,
+        #[serde(rename = "by", default)]
+        pub decided_by: Option<String>,
+    }
+
     #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
     pub(in crate::sockets) struct Lock {
         #[serde(rename = "sid")]
@@ -1,4 +1,7 @@
+pub mod connection;
 mod handlers;
 pub mod manager;
 pub mod message_types;
+pub mod outbox;
 mod starter_impl;
+pub mod subscriptions;
@@ -0,0 +1,254 @@
+use std::sync::Mutex;
+
+use socket_manager::message::Message;
+use tauri_plugin_store::StoreExt;
+
+use crate::debug;
+use crate::sockets::message_types::application::Payload;
+use crate::state::store::Storable;
+
+/// A soft cap on the messages kept waiting for the server. Marks are
+/// superseded by later marks of the same movement, so this is only reached
+/// when the judge has been offline for a very long time. Marks and locks are
+/// never dropped to stay within it, so the outbox can grow past it; once
+/// nothing else is left to drop, every edit of the sheet is refused instead.
+const CAPACITY: usize = 500;
+const FIRST_RETRY: chrono::Duration = chrono::Duration::seconds(10);
+const LONGEST_RETRY: chrono::Duration = chrono::Duration::minutes(5);
+/// Where messages were kept before the outbox, which resent them forever
+const LEGACY_KEY: &str = "STORED_MESSAGES";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct OutboxEntry {
+    pub message: Message<Payload>,
+    pub attempts: u32,
+    pub next_attempt: chrono::DateTime<chrono::Utc>,
+}
+
+/// Messages sent to the server which it has not yet acknowledged, keyed by
+/// the ULID of the message so the server's `Ack` can remove them.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct Outbox {
+    entries: Vec<OutboxEntry>,
+}
+
+impl Outbox {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Whether the outbox is at capacity with nothing left that could be
+    /// dropped to make room for another mark
+    pub fn is_full(&self) -> bool {
+        self.entries.len() >= CAPACITY
+            && !self.entries.iter().any(|x| x.message.message.evictable())
+    }
+
+    /// Keeps the message until the server acknowledges it, dropping any
+    /// earlier message it replaces. When full, the oldest message that may
+    /// be dropped makes room, and a mark or lock is kept regardless.
+    pub fn push(&mut self, message: Message<Payload>) {
+        if !message.message.needs_ack() {
+            return;
+        }
+        self.entries
+            .retain(|x| !message.message.supersedes(&x.message.message));
+        if self.entries.len() >= CAPACITY {
+            match self
+                .entries
+                .iter()
+                .position(|x| x.message.message.evictable())
+            {
+                Some(index) => {
+                    let dropped = self.entries.remove(index);
+                    debug!(yellow, "Outbox full, dropped {}", dropped.message.id);
+                }
+                None if message.message.evictable() => {
+                    debug!(yellow, "Outbox full, not keeping {}", message.id);
+                    return;
+                }
+                None => {}
+            }
+        }
+        self.entries.push(OutboxEntry {
+            message,
+            attempts: 0,
+            next_attempt: chrono::Utc::now() + FIRST_RETRY,
+        });
+    }
+
+    pub fn acknowledge(&mut self, id: &ulid::Ulid) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|x| x.message.id != *id);
+        before != self.entries.len()
+    }
+
+    /// The messages due to be sent again, each pushed back twice as far as
+    /// the last time until they are retried every few minutes
+    pub fn take_due(&mut self, now: chrono::DateTime<chrono::Utc>) -> Vec<Message<Payload>> {
+        let mut due = vec![];
+        for entry in self.entries.iter_mut().filter(|x| x.next_attempt <= now) {
+            entry.attempts += 1;
+            let backoff = FIRST_RETRY * 2i32.saturating_pow(entry.attempts.min(16));
+            entry.next_attempt = now + backoff.min(LONGEST_RETRY);
+            due.push(entry.message.clone());
+        }
+        due
+    }
+
+    /// Whether a message has gone unacknowledged even after being sent again
+    pub fn stalled(&self) -> bool {
+        self.entries.iter().any(|x| x.attempts > 1)
+    }
+
+    /// Makes everything due now, such as after reconnecting when the
+    /// messages were likely lost with the old connection
+    pub fn retry_now(&mut self) {
+        let now = chrono::Utc::now();
+        for entry in self.entries.iter_mut() {
+            entry.next_attempt = now;
+        }
+    }
+}
+
+pub struct ManagedOutbox(Mutex<Outbox>);
+
+impl ManagedOutbox {
+    pub fn load(handle: &tauri::AppHandle) -> Self {
+        if let Ok(store) = handle.store(env!("STORE_URI")) {
+            store.delete(LEGACY_KEY);
+        }
+        let outbox = Outbox::retrieve(handle).unwrap_or_default();
+        debug!(dim, "Outbox has {} unacknowledged messages", outbox.len());
+        Self(Mutex::new(outbox))
+    }
+
+    /// Changes the outbox and writes it through to the store, so nothing
+    /// waiting on the server is lost if the app is closed
+    pub fn update<R>(
+        &self,
+        handle: &tauri::AppHandle,
+        f: impl FnOnce(&mut Outbox) -> R,
+    ) -> Option<R> {
+        let mut outbox = self.0.lock().ok()?;
+        let ret = f(&mut outbox);
+        outbox.store(handle);
+        Some(ret)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().map_or(0, |x| x.len())
+    }
+
+    pub fn stalled(&self) -> bool {
+        self.0.lock().is_ok_and(|x| x.stalled())
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.0.lock().is_ok_and(|x| x.is_full())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use socket_manager::message::Message;
+    use ulid::Ulid;
+
+    use super::{Outbox, CAPACITY};
+    use crate::commands::alert_manager::AlertType;
+    use crate::domain::penalties::BroadcastPenaltyVariety;
+    use crate::domain::position::Position;
+    use crate::sockets::message_types::application::{CompetitionMessage, Payload, Penalty};
+    use crate::sockets::message_types::common::Signal;
+
+    fn mark(sheet_id: Ulid, number: u16) -> Message<Payload> {
+        Message::new(Payload::mark(sheet_id, number, None, None))
+    }
+
+    fn signal(sheet_id: Ulid) -> Message<Payload> {
+        Message::new(Payload::Competition(CompetitionMessage::Signal(
+            Signal::new(sheet_id, AlertType::Blood, true, Position::default()),
+        )))
+    }
+
+    fn penalty(sheet_id: Ulid, variety: BroadcastPenaltyVariety, quantity: u8) -> Message<Payload> {
+        Message::new(Payload::Competition(CompetitionMessage::Penalty(
+            Penalty::new(sheet_id, variety, quantity, Position::default()),
+        )))
+    }
+
+    fn full_of_marks(sheet_id: Ulid) -> Outbox {
+        let mut outbox = Outbox::default();
+        for number in 0..CAPACITY as u16 {
+            outbox.push(mark(sheet_id, number));
+        }
+        outbox
+    }
+
+    #[test]
+    fn a_full_outbox_never_drops_marks() {
+        let sheet_id = Ulid::new();
+        let mut outbox = full_of_marks(sheet_id);
+        assert!(outbox.is_full());
+
+        outbox.push(signal(sheet_id));
+        assert_eq!(outbox.len(), CAPACITY);
+        assert!(outbox
+            .entries
+            .iter()
+            .all(|x| !x.message.message.evictable()));
+
+        outbox.push(mark(sheet_id, CAPACITY as u16));
+        assert_eq!(outbox.len(), CAPACITY + 1);
+    }
+
+    #[test]
+    fn a_full_outbox_drops_other_messages_first() {
+        let sheet_id = Ulid::new();
+        let mut outbox = Outbox::default();
+        outbox.push(signal(sheet_id));
+        for number in 1..CAPACITY as u16 {
+            outbox.push(mark(sheet_id, number));
+        }
+        assert!(!outbox.is_full());
+
+        outbox.push(mark(sheet_id, CAPACITY as u16));
+        assert_eq!(outbox.len(), CAPACITY);
+        assert!(outbox.is_full());
+    }
+
+    #[test]
+    fn penalties_replace_earlier_counts_of_the_same_source() {
+        let sheet_id = Ulid::new();
+        let mut outbox = Outbox::default();
+        outbox.push(penalty(
+            sheet_id,
+            BroadcastPenaltyVariety::ErrorsOfCourse,
+            1,
+        ));
+        outbox.push(penalty(
+            sheet_id,
+            BroadcastPenaltyVariety::ErrorsOfCourse,
+            2,
+        ));
+        assert_eq!(outbox.len(), 1);
+
+        outbox.push(penalty(
+            sheet_id,
+            BroadcastPenaltyVariety::TechnicalPenalty,
+            1,
+        ));
+        outbox.push(penalty(
+            Ulid::new(),
+            BroadcastPenaltyVariety::ErrorsOfCourse,
+            1,
+        ));
+        assert_eq!(outbox.len(), 3);
+    }
+}
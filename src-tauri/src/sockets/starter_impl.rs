@@ -1,4 +1,12 @@
-use crate::domain::starter::Starter;
+use crate::commands::alert_manager::{set_alert, set_status_alert};
+use crate::commands::warnings::manager::Warnings;
+use crate::domain::amendment::Correction;
+use crate::domain::edit_guard::SheetEdit;
+use crate::domain::history::History;
+use crate::domain::position::Position;
+use crate::domain::starter::{Starter, StarterResult};
+
+use super::message_types::common;
 
 impl Starter {
     // --- Impose Handlers --- //
@@ -6,7 +14,12 @@ impl Starter {
     // of logic across both the state and memory.
     // They should provide an exact update of the state
     // in both cases
-    pub(in crate::sockets) fn impose_lock(&mut self, lock: &super::message_types::server::Lock) {
+    /// Returns the marks the scorer corrected, against what the judge had given
+    pub(in crate::sockets) fn impose_lock(
+        &mut self,
+        lock: &super::message_types::server::Lock,
+    ) -> Vec<Correction> {
+        let mut corrections = vec![];
         if let Some(scoresheet) = self.scoresheets.first_mut() {
             scoresheet.rank = lock.rank;
             scoresheet.locked = lock.locked;
@@ -26,8 +39,37 @@ impl Starter {
                 scoresheet.art_penalties = ap;
             }
 
-            // TODO: Add check over all marks
+            // the scores are likewise only sent when the scorer
+            // has corrected them. The judge's remarks are kept
+            for incoming in lock.scores.iter().flatten() {
+                match scoresheet
+                    .scores
+                    .iter_mut()
+                    .find(|x| x.number == incoming.number)
+                {
+                    Some(current) if current.mark == incoming.mark => (),
+                    Some(current) => {
+                        corrections.push(Correction {
+                            number: incoming.number,
+                            original: current.mark,
+                            corrected: incoming.mark,
+                        });
+                        current.mark = incoming.mark;
+                    }
+                    None => {
+                        corrections.push(Correction {
+                            number: incoming.number,
+                            original: None,
+                            corrected: incoming.mark,
+                        });
+                        scoresheet.scores.push(incoming.clone());
+                    }
+                }
+            }
         }
+        // undoing must not take back what the scorer set
+        self.rebase_history();
+        corrections
     }
     pub(in crate::sockets) fn impose_trend(&mut self, trend: &super::message_types::server::Trend) {
         if let Some(scoresheet) = self.scoresheets.first_mut() {
@@ -37,4 +79,68 @@ impl Starter {
             }
         }
     }
+    /// Clears the judge's scoresheet back to how it was before marking began
+    pub(in crate::sockets) fn impose_reset(&mut self) {
+        if let Some(scoresheet) = self.scoresheets.first_mut() {
+            scoresheet.scores = vec![];
+            scoresheet.score = None;
+            scoresheet.rank = None;
+            scoresheet.errors = 0;
+            scoresheet.tech_penalties = 0;
+            scoresheet.art_penalties = 0;
+            scoresheet.summary = None;
+            scoresheet.locked = false;
+            scoresheet.warning_manager = Warnings::default();
+            // undoing would otherwise bring back marks from before the reset
+            scoresheet.history = History::default();
+            scoresheet.amendments = vec![];
+        }
+        self.score = None;
+        self.status = StarterResult::Upcoming;
+        self.warnings = vec![];
+    }
+    /// Records a signal another judge raised or took down
+    pub(in crate::sockets) fn impose_signal(
+        &mut self,
+        signal: &common::Signal,
+        position: &Position,
+    ) {
+        if let Some(scoresheet) = self.scoresheets.first_mut() {
+            if let Some(warning) = scoresheet.warning_manager.get_mut(&signal.signal) {
+                warning.set(position.clone(), signal.active);
+            }
+        }
+        set_alert(
+            &mut self.warnings,
+            signal.signal.clone(),
+            position,
+            signal.active,
+        );
+        self.warnings.retain(|x| !x.is_empty());
+    }
+    /// Takes the status a judge of the jury gave the starter, unless the
+    /// judge's own scoresheet is locked against changing it. Returns
+    /// whether the status was taken.
+    pub(in crate::sockets) fn impose_status(
+        &mut self,
+        status: &common::Status,
+        position: &Position,
+    ) -> bool {
+        if self
+            .scoresheets
+            .first()
+            .is_some_and(|x| x.check_edit(SheetEdit::Status).is_err())
+        {
+            return false;
+        }
+        if let Some(scoresheet) = self.scoresheets.first_mut() {
+            scoresheet
+                .warning_manager
+                .set_status(position, &status.status);
+        }
+        set_status_alert(&mut self.warnings, position, &status.status);
+        self.status = status.status.clone();
+        self.rebase_history();
+        true
+    }
 }
@@ -0,0 +1,79 @@
+use std::sync::Mutex;
+
+use socket_manager::SocketError;
+use tauri::Manager;
+use ulid::Ulid;
+
+use super::manager::ManagedSocket;
+use super::message_types::application::{CompetitionMessage, Payload};
+
+/// The competition the judge is following. The server forgets it with the
+/// connection, so it is subscribed to again on every new one.
+#[derive(Default)]
+pub struct ManagedSubscriptions(Mutex<Option<Ulid>>);
+
+impl ManagedSubscriptions {
+    /// Follows the competition, subscribing straight away if connected.
+    /// The server only sends one competition at a time, so any other
+    /// competition being followed is unsubscribed from first.
+    pub async fn subscribe(
+        &self,
+        handle: &tauri::AppHandle,
+        competition_id: Ulid,
+    ) -> Result<(), SocketError> {
+        let previous = self
+            .0
+            .lock()
+            .ok()
+            .and_then(|mut x| x.replace(competition_id));
+        let Some(socket) = handle.try_state::<ManagedSocket>() else {
+            // the first connection will subscribe to it
+            return Ok(());
+        };
+        if previous.is_some_and(|x| x != competition_id) {
+            socket
+                .send(Payload::Competition(CompetitionMessage::Unsubscribe))
+                .await?;
+        }
+        socket.send(Payload::Subscribe { competition_id }).await
+    }
+
+    /// Stops following the competition, such as when the judge leaves its
+    /// scoresheet. Nothing is sent if it is not the one being followed.
+    pub async fn unsubscribe(
+        &self,
+        handle: &tauri::AppHandle,
+        competition_id: Ulid,
+    ) -> Result<(), SocketError> {
+        let was_subscribed = self.0.lock().is_ok_and(|mut x| {
+            let following = *x == Some(competition_id);
+            if following {
+                *x = None;
+            }
+            following
+        });
+        match handle.try_state::<ManagedSocket>() {
+            Some(socket) if was_subscribed => {
+                socket
+                    .send(Payload::Competition(CompetitionMessage::Unsubscribe))
+                    .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Forgets the competition without telling the server, for when the
+    /// server has ended the subscription itself
+    pub fn forget(&self) -> bool {
+        self.0.lock().is_ok_and(|mut x| x.take().is_some())
+    }
+
+    /// Subscribes to the competition being followed, on a new connection
+    pub async fn resubscribe(&self, socket: &ManagedSocket) -> Result<(), SocketError> {
+        let competition = self.0.lock().ok().and_then(|x| *x);
+        match competition {
+            Some(competition_id) => socket.send(Payload::Subscribe { competition_id }).await,
+            None => Ok(()),
+        }
+    }
+}
@@ -10,6 +10,7 @@ use crate::commands::replace_director::ReplaceDirector;
 use crate::debug;
 use crate::domain::competition::Competition;
 use crate::domain::dressage_test::DressageTest;
+use crate::domain::edit_guard::{EditRefused, SheetEdit};
 use crate::domain::ground_jury_member::GroundJuryMember;
 use crate::domain::judge::Judge;
 use crate::domain::scoresheet::Scoresheet;
@@ -106,12 +107,10 @@ impl ApplicationState {
         let show = self.show.as_ref()?;
         show.competitions.iter().find(|x| x.id == *id)
     }
-    // FIXME: To be used
-    #[allow(unused)]
-    pub fn competition_mut(&self) -> Option<&Competition> {
+    pub fn competition_mut(&mut self) -> Option<&mut Competition> {
         let id = self.competition_id.as_ref()?;
-        let show = self.show.as_ref()?;
-        show.competitions.iter().find(|x| x.id == *id)
+        let show = self.show.as_mut()?;
+        show.competitions.iter_mut().find(|x| x.id == *id)
     }
     pub fn starter_from_sheet_ulid_mut(&mut self, ulid: &ulid::Ulid) -> Option<&mut Starter> {
         let show = self.show.as_mut()?;
@@ -157,6 +156,26 @@ impl ApplicationState {
         competitor
     }
 
+    /// Runs an edit of the current starter and records what it changed in
+    /// the history of the judge's scoresheet.
+    pub fn tracked<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let snapshot = self.starter().map(Starter::snapshot);
+        let ret = f(self);
+        if let Some((starter, snapshot)) = self.starter_mut().zip(snapshot) {
+            starter.record_since(snapshot);
+        }
+        ret
+    }
+    /// The provisional rank of the starter in the current competition,
+    /// worked out afresh so that it follows every mark given offline
+    pub fn rank(&self, starter: &Starter) -> Option<u16> {
+        self.competition()?.ranking().rank(starter)
+    }
+    /// Checks the edit against the judge's scoresheet. Without a sheet there
+    /// is nothing to protect, so the edit is left to fail on its own.
+    pub fn check_edit(&self, edit: SheetEdit) -> Result<(), EditRefused> {
+        self.scoresheet().map_or(Ok(()), |x| x.check_edit(edit))
+    }
     pub fn scoresheet_mut(&mut self) -> Option<&mut Scoresheet> {
         self.starter_mut()?.scoresheets.first_mut()
     }
@@ -263,6 +282,13 @@ impl Debouncer {
             let _ = cancel_sender.send(false);
         }
     }
+    /// Cancels every pending task without running it
+    pub fn cancel_all(&self) {
+        let mut tasks = self.0.lock().unwrap();
+        for (_, cancel_sender) in tasks.drain() {
+            let _ = cancel_sender.send(false);
+        }
+    }
     pub fn execute_immediately(&self, index: u16) {
         let mut tasks = self.0.lock().unwrap();
         if let Some(cancel_sender) = tasks.remove(&index) {
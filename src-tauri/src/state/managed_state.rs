@@ -40,6 +40,9 @@ impl ManagedApplicationState {
                     old_state.get_judge(),
                     old_state.page
                 );
+                for diagnostic in old_state.show.iter().flat_map(|s| s.diagnostics()) {
+                    debug!(yellow, "{diagnostic}");
+                }
                 new_state.write(move |x| {
                     // Overwrite portions of the application
                     // state with stored values
@@ -7,6 +7,7 @@ use crate::commands::signature::Signature;
 use crate::debug;
 use crate::domain::show::{Show, Shows};
 use crate::domain::SurrealId;
+use crate::sockets::outbox::Outbox;
 
 use super::application_state::ApplicationId;
 use super::ApplicationState;
@@ -56,6 +57,14 @@ impl Storable for ApplicationId {
     type Key = ();
     const KEY: &str = "APPLICATION_ID";
 }
+impl Storable for Outbox {
+    type Key = ();
+    const KEY: &str = "OUTBOX";
+    // written with every message, so quietly
+    fn store(&self, handle: &tauri::AppHandle) {
+        _set_helper(get_store_helper(handle), Self::KEY, self);
+    }
+}
 
 pub trait Storable: serde::Serialize + serde::de::DeserializeOwned + Sized {
     type Key;
@@ -70,7 +70,8 @@ pub fn render_list(competitions: Vec<Competition>) -> Lazy<impl Fn(&mut std::str
     }
 }
 fn competition_listing<'a>(x: &'a Competition) -> Lazy<impl Fn(&mut String) + use<'a>> {
-    rsx! {
+    let diagnostics = x.diagnostics();
+    rsx_move! {
         <li
             tx-goto="scoresheet"
             tx-id=x.get_id()
@@ -90,6 +91,13 @@ fn competition_listing<'a>(x: &'a Competition) -> Lazy<impl Fn(&mut String) + us
                 align-items:center; border-radius:0.7rem; justify-content:center; display:flex;
                 block-size:3rem; align-self:center; inline-size:3rem;font-size:2rem; font-weight:500"
             >{x.jury.first().map(|j|j.position.to_string()).unwrap_or_default()}</div>
+            @if !diagnostics.is_empty() {
+                <ul class="test-diagnostics" style="grid-column:1/3; margin:0; color:var(--error); font-size:var(--text-info)">
+                    @for diagnostic in diagnostics.iter() {
+                        <li>{diagnostic.to_string()}</li>
+                    }
+                </ul>
+            }
         </li>
     }
 }
@@ -2,6 +2,7 @@ use super::TxAttributes;
 use crate::commands::replace_director::{ReplaceDirector, ResponseDirector};
 use crate::state::ManagedApplicationState;
 use crate::templates::icons;
+use crate::templates::scoresheet::{deduction_report, trend_breakdown};
 use crate::traits::Entity;
 use hypertext::*;
 
@@ -14,7 +15,8 @@ pub async fn result(state: tauri::State<'_, ManagedApplicationState>) -> Respons
             let judge = app_state.get_judge_id();
             let test = app_state.get_test();
             let mut starters = competition.starters.clone();
-            starters.sort_by_key(|x| x.status.abbreviate());
+            let ranking = competition.ranking();
+            starters.sort_by_key(|x| ranking.rank(x).unwrap_or(u16::MAX));
             hypertext::rsx! {
                 <main id="page--results" style="position:fixed; inset:0; display:grid; grid: auto 1fr / 1fr;background:white">
                 <header>
@@ -26,21 +28,35 @@ pub async fn result(state: tauri::State<'_, ManagedApplicationState>) -> Respons
                 </header>
                 <div style="font-size:var(--text-info); overflow-y: auto">
                 @for starter in starters.iter() {
+                @let panel = starter.panel(competition);
+                @let precision = starter.precision(competition);
                 <details name="testsheet">
                     <summary style="border-bottom:1px solid grey">
                         <div class="main-result" style="display:flex;position:relative;width:100%;padding-inline:1rem;">
-                            <div style="width:2.1rem;font-size:1.2rem;font-weight:500">{starter.status.abbreviate()}</div>
-                            <div style="width:4.2rem;font-size:1.2rem;font-weight:500">{starter.score.map(|s|s.round(3))}</div>
+                            <div style="width:2.1rem;font-size:1.2rem;font-weight:500">{match (starter.status.rank(), ranking.rank(starter)) {
+                                (Some(0), Some(r)) => format!("({r})"),
+                                _ => starter.status.abbreviate(),
+                            }}</div>
+                            <div style="width:4.2rem;font-size:1.2rem;font-weight:500">{match (starter.score, panel.score) {
+                                (Some(score), _) => Some(precision.format(score)),
+                                (None, Some(score)) if panel.provisional => Some(format!("{} prov.", precision.format(score))),
+                                (None, score) => score.map(|s| precision.format(s)),
+                            }}</div>
                             <div style="width:30vw">
                                 <div>{starter.name()}</div>
                                 <div>{starter.horse()}</div>
                             </div>
                             <div
                                 style="position:absolute;height:40%;width:calc(70vw - 5.8rem);background:var(--theme);inset:auto 0 0 auto;clip-path:polygon(0.7rem 0, 100% 0, 100% 100%, 0 100%);z-index:-1"></div>
-                            @for scoresheet in starter.scoresheets.iter() {
-                                <div style="flex: 1 0 auto;align-self:start; margin-top:.3rem; text-align:center;">
-                                    <div>{scoresheet.score.map(|s|s.round(3))}</div>
-                                    <div style="color:var(--foreground); padding-top:.2rem">{scoresheet.rank.map(|r|r.to_string())}</div>
+                            @for contribution in panel.contributions.iter() {
+                                @let scoresheet = starter.scoresheets.iter().find(|s| s.id == contribution.sheet_id);
+                                <div style=format!("flex: 1 0 auto;align-self:start; margin-top:.3rem; text-align:center;{}", if contribution.counted {""} else {"opacity:0.5"})>
+                                    <div style="font-weight:bold">{contribution.position.to_string()}</div>
+                                    <div>{contribution.score.map(|s| precision.format(s))}</div>
+                                    <div style="color:var(--foreground); padding-top:.2rem">{scoresheet.and_then(|s| s.rank).map(|r|r.to_string())}</div>
+                                    @if let Some(breakdown) = scoresheet.zip(test).and_then(|(s, t)| s.trend_breakdown(t)) {
+                                        {trend_breakdown(&breakdown)}
+                                    }
                                 </div>
                             }
                         </div>
@@ -56,13 +72,25 @@ pub async fn result(state: tauri::State<'_, ManagedApplicationState>) -> Respons
                                     style=format!("grid-row: {nr}; grid-column:1; min-width: 9rem", nr = exercise.number+1)
                                 >{exercise.number}. {&exercise.abbreviation}</div>
                             }
+                            <div style=format!("grid-row: {nr}; grid-column:1", nr = test.movements.len()+2)>"Deductions"</div>
                             @let number_of_scoresheets = starter.scoresheets.len();
                             @for (scoresheet, i) in starter.scoresheets.iter().zip(0..number_of_scoresheets) {
-                                <div style=format!("grid-row: 1; grid-column: {i}", i = i+2)>{scoresheet.score}</div>
+                                <div style=format!("grid-row: 1; grid-column: {i}", i = i+2)>{scoresheet.score.map(|s| precision.format(s))}</div>
                                 @let mut comments:Vec<String> = Vec::with_capacity(number_of_scoresheets);
+                                @let corrections = scoresheet.corrections();
                                 @for movement in scoresheet.scores.iter() {
-                                    <div style=format!("grid-row: {nr}; grid-column: {i}", i = i+2, nr = movement.number+1)>{movement.mark}</div>
+                                    <div style=format!("grid-row: {nr}; grid-column: {i}", i = i+2, nr = movement.number+1)>
+                                        @if let Some(correction) = corrections.iter().find(|x| x.number == movement.number) {
+                                            <s style="opacity:0.6">{correction.original.map(|x| x.to_string())}</s>" "
+                                            <span class="corrected-mark" style="color:var(--theme)">{movement.mark}</span>
+                                        } @else {
+                                            {movement.mark}
+                                        }
+                                    </div>
                                 }
+                                <div style=format!("grid-row: {nr}; grid-column: {i}", i = i+2, nr = test.movements.len()+2)>
+                                    {deduction_report(scoresheet.deduction_report(test))}
+                                </div>
                             }
                         }
                     </div>
@@ -0,0 +1,61 @@
+use hypertext::{rsx_move, GlobalAttributes, Lazy, Raw};
+
+use crate::domain::amendment::{Amendment, AmendmentStatus};
+use crate::templates::{html_elements, TxAttributes};
+
+const DIALOG_STYLE: &str =
+    "pointer-events:all; border-radius:var(--corner-size); font-size:var(--text-info)";
+
+pub fn amendment_form<'a>(error: Option<&'a str>) -> Lazy<impl Fn(&mut String) + 'a> {
+    rsx_move! {
+        <dialog id="amendment-dialog" style=DIALOG_STYLE>
+            <form tx-command="request_amendment" tx-trigger="submit">
+                <h2>"Request an amendment"</h2>
+                <p>"The chief judge or scorer will be asked to reopen these movements"</p>
+                <label style="display:block">"Movements"
+                    <input type="text" name="movements" placeholder="e.g. 4, 7" style="display:block; inline-size:100%">
+                </label>
+                <label style="display:block; margin-block-start:var(--padding)">"Reason"
+                    <textarea name="reason" rows="3" style="display:block; inline-size:100%"></textarea>
+                </label>
+                @if let Some(error) = error {
+                    <p style="color:var(--error)">{error}</p>
+                }
+                <div style="display:flex; gap:var(--padding); justify-content:end; margin-block-start:var(--padding)">
+                    <button type="button" onclick="document.querySelector('#amendment-dialog')?.close()">"Cancel"</button>
+                    <button type="submit">"Send request"</button>
+                </div>
+            </form>
+        </dialog>
+        <script>{Raw("document.querySelector('#amendment-dialog')?.showModal();")}</script>
+    }
+}
+
+/// Shows the judge where their latest amendment request is up to
+pub fn amendment_status<'a>(amendment: Option<&'a Amendment>) -> Lazy<impl Fn(&mut String) + 'a> {
+    let message = amendment.map(|x| {
+        let movements = x
+            .movements
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let by = x
+            .decided_by
+            .as_deref()
+            .map_or(String::new(), |x| format!(" by {x}"));
+        match x.status {
+            AmendmentStatus::Pending => format!("Amendment of {movements} is waiting for approval"),
+            AmendmentStatus::Approved => {
+                format!("Amendment of {movements} approved{by}, mark them again and confirm")
+            }
+            AmendmentStatus::Rejected => format!("Amendment of {movements} was rejected{by}"),
+            AmendmentStatus::Applied => format!("Movements {movements} have been corrected"),
+        }
+    });
+    rsx_move! {
+        @if let Some(ref message) = message {
+            <div class="amendment-status" style="font-size:var(--text-info); color:var(--theme)">{message}</div>
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use hypertext::{rsx_move, GlobalAttributes, Lazy};
+
+use crate::sockets::connection::ConnectionState;
+use crate::templates::html_elements;
+
+pub fn connection_indicator(state: ConnectionState, unsent: usize) -> Lazy<impl Fn(&mut String)> {
+    let color = match state {
+        ConnectionState::Online => "var(--theme)",
+        ConnectionState::Connecting | ConnectionState::Degraded => "orange",
+        ConnectionState::Offline | ConnectionState::AuthFailed => "var(--error)",
+    };
+    rsx_move! {
+        <div
+            title="Connection to the server"
+            style="display:flex; align-items:center; gap:var(--padding); font-size:var(--text-info); margin-inline-end:1rem"
+        >
+            <span style=format!("display:inline-block; inline-size:0.6rem; aspect-ratio:1; border-radius:50%; background:{color}")></span>
+            <span>{state.label()}</span>
+            @if unsent > 0 {
+                <span style="opacity:0.7">{format!("({unsent} unsent)")}</span>
+            }
+        </div>
+    }
+}
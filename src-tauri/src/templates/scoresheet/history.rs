@@ -0,0 +1,60 @@
+use hypertext::{rsx_move, GlobalAttributes, Lazy, Raw};
+
+use crate::domain::history::History;
+use crate::templates::{html_elements, TxAttributes};
+
+pub fn history_controls<'a>(history: &'a History, locked: bool) -> Lazy<impl Fn(&mut String) + 'a> {
+    let can_undo = !locked && history.can_undo();
+    let can_redo = !locked && history.can_redo();
+    rsx_move! {
+        <div style="display:flex; gap:var(--padding); margin-inline-end:1rem; font-size:var(--text-info)">
+            @if can_undo {
+                <button type="button" title="Undo" tx-command="undo">"↶"</button>
+            } @else {
+                <button type="button" title="Undo" disabled>"↶"</button>
+            }
+            @if can_redo {
+                <button type="button" title="Redo" tx-command="redo">"↷"</button>
+            } @else {
+                <button type="button" title="Redo" disabled>"↷"</button>
+            }
+            <button type="button" title="History" tx-command="show_history">"☰"</button>
+        </div>
+    }
+}
+
+pub fn history_trail<'a>(
+    history: &'a History,
+    message: Option<String>,
+) -> Lazy<impl Fn(&mut String) + 'a> {
+    let undone_from = history.events.len() - history.undone;
+    rsx_move! {
+        <dialog
+            id="history-dialog"
+            style="pointer-events:all; border-radius:var(--corner-size); max-block-size:70vh; overflow-y:auto"
+        >
+            <form method="dialog">
+                <h2>"Scoresheet history"</h2>
+                @if history.is_empty() {
+                    <p style="font-size:var(--text-info)">"No changes have been made yet"</p>
+                }
+                <ol style="font-size:var(--text-info); padding-inline-start:1.5rem">
+                    @for (i, event) in history.events.iter().enumerate() {
+                        <li style=(if i >= undone_from {"opacity:0.5; text-decoration:line-through"} else {""})>
+                            <span style="color:grey">{event.at.format("%H:%M:%S").to_string()}" "</span>
+                            {event.changes.iter().map(|x| x.to_string()).collect::<Vec<_>>().join("; ")}
+                        </li>
+                    }
+                </ol>
+                @if let Some(ref message) = message {
+                    <p style="font-size:var(--text-info)">{message}</p>
+                }
+                <div style="display:flex; gap:var(--padding); justify-content:end">
+                    <button type="button" tx-command="export_history">"Export"</button>
+                    <button>"Close"</button>
+                </div>
+            </form>
+        </dialog>
+        <script>{Raw("document.querySelector('#history-dialog')?.showModal();")}</script>
+    }
+}
@@ -1,3 +1,6 @@
+pub mod amendment;
+pub mod connection;
+pub mod history;
 pub mod start_list_bar;
 pub mod warnings;
 pub mod warnings_bar;
@@ -12,13 +15,18 @@ use crate::commands::alert_manager::AlertManager;
 use crate::commands::replace_director::{ReplaceDirector, ResponseDirector};
 use crate::commands::signature::Signature;
 use crate::debug;
+use crate::domain::amendment::Correction;
 use crate::domain::competition::Competition;
+use crate::domain::deductions::DeductionReport;
 use crate::domain::dressage_test::DressageTest;
-use crate::domain::dressage_test::{Exercise, TestSheetType};
+use crate::domain::dressage_test::{DifficultyEvaluation, Exercise, Precision, TestSheetType};
+use crate::domain::edit_guard::EditRefused;
 use crate::domain::ground_jury_member::GroundJuryMember;
 use crate::domain::ground_jury_member::JuryAuthority;
-use crate::domain::scoresheet::{ScoredMark, Scoresheet};
-use crate::domain::starter::StarterResult;
+use crate::domain::scoresheet::{ScoredMark, Scoresheet, TrendBreakdown};
+use crate::domain::starter::{Starter, StarterResult};
+use crate::sockets::connection::ManagedConnection;
+use crate::sockets::outbox::ManagedOutbox;
 use crate::state::ManagedApplicationState;
 
 use super::error::screen_error;
@@ -26,50 +34,67 @@ use super::error::screen_error;
 pub async fn scoresheet(
     state: tauri::State<'_, ManagedApplicationState>,
     alert_manager: tauri::State<'_, AlertManager>,
+    handle: tauri::AppHandle,
 ) -> ResponseDirector {
-    let (competition, show, starter) = state.read_async(|app_state| Ok((
-        app_state
-            .competition()
-            .ok_or_else(|| screen_error("Competition Not Found"))?
-            .clone(),
+    use tauri::Manager;
 
-        app_state
-            .show
-            .clone()
-            .ok_or_else(|| screen_error("Show Not Found"))?,
-
-        app_state
-            .starter()
-            .ok_or_else(|| screen_error("Starter not found"))?
-            .clone(),
-    ))
-    ).await??;
+    let connection = handle.state::<ManagedConnection>().get();
+    let unsent = handle.state::<ManagedOutbox>().len();
+    let (competition, show, starter, test) = state
+        .read_async(|app_state| {
+            let competition = app_state
+                .competition()
+                .ok_or_else(|| screen_error("Competition Not Found"))?;
+            Ok((
+                competition.clone(),
+                app_state
+                    .show
+                    .clone()
+                    .ok_or_else(|| screen_error("Show Not Found"))?,
+                app_state
+                    .starter()
+                    .ok_or_else(|| screen_error("Starter not found"))?
+                    .clone(),
+                app_state
+                    .get_test()
+                    .or_else(|| competition.tests.first())
+                    .ok_or_else(|| screen_error("Testsheet not found"))?
+                    .clone(),
+            ))
+        })
+        .await??;
 
+    // a broken test sheet would otherwise panic part way through marking,
+    // anything less is left to the notifications
+    let fatal = test
+        .validate()
+        .into_iter()
+        .filter(|x| x.is_fatal())
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>();
+    if !fatal.is_empty() {
+        let reasons = fatal.join(". ");
+        return Err(screen_error(&format!(
+            "The test sheet cannot be scored. {reasons}"
+        )));
+    }
 
     let scoresheet = starter
         .scoresheets
         .first()
         .ok_or_else(|| screen_error("Scoresheet not found for this competitor"))?;
 
-    let athlete_name = format!(
-        "{} {}",
-        starter.competitor.first_name, starter.competitor.last_name
-    );
-    let horse_name = starter.competitor.horse_name.to_string();
     let judge = competition
         .jury
         .first()
         .ok_or_else(|| screen_error("Judge not found"))?;
 
-    let test = competition
-        .tests
-        .first()
-        .ok_or_else(|| screen_error("Testsheet not found"))?;
-
+    let test = &test;
     let test_name = test.name.as_str();
     let is_freestyle_mode = test.test_type == TestSheetType::Freestyle;
     let scoresheet_row_html = scoresheet_rows(test, scoresheet.clone(), judge, is_freestyle_mode);
     let warnings = warnings::get_warnings(alert_manager);
+    let ranking = competition.ranking();
 
     Ok(ReplaceDirector::page(rsx! {
 	<main
@@ -87,12 +112,7 @@ pub async fn scoresheet(
 				style="flex: 0 1 100%; padding: 0.5rem 0 0.5rem 0.5rem;display:flex;
 					flex-direction:column;justify-content:center"
 			>
-				<h1 id="athlete-name">{&athlete_name}</h1>
-				<h2 id="horse-name">
-					<span style="color:var(--theme);font-weight:bold">{&starter.competitor.comp_no}</span>
-					<span style="padding-inline:0.5rem">|</span>
-					<span>{&horse_name}</span>
-				</h2>
+				<hgroup id="starter-names">{starter_names(&starter)}</hgroup>
 				<h3 id="test-name" style="font-size:var(--text-info);">{test_name}</h3>
 			</section>
 			<section
@@ -110,9 +130,11 @@ pub async fn scoresheet(
 				style="flex: 0 1 100%; display:flex; justify-content: end; align-items:center;
 				padding-inline-end:1rem"
 			>
+				<div id="connection-indicator">{connection::connection_indicator(connection, unsent)}</div>
+				<div id="history-controls">{history::history_controls(&scoresheet.history, scoresheet.locked)}</div>
 				<div style="text-align:end; margin-inline-end: 1rem;">
 					<output id="header-trend">{ if !judge.judge.prefs.hide_trend {
-						Some(header_trend(scoresheet.score, scoresheet.rank, false))
+						Some(header_trend(scoresheet.score, scoresheet.rank, false, scoresheet.trend_breakdown(test), test.precision))
 						} else {None} }</output>
 
 					<h3 style="font-size:0.6rem;">{ format!("{} {}", judge.judge.first_name, judge.judge.last_name) }</h3>
@@ -190,7 +212,7 @@ pub async fn scoresheet(
 							style="vertical-align: center; text-align: start; border:none"
 							id="confirm-marks"
 						>
-                        {get_confirm_or_signature(scoresheet.locked, judge.judge.signature.clone())}
+                        {get_confirm_or_signature(scoresheet.locked, !scoresheet.amendable().is_empty(), judge.judge.signature.clone())}
 						</td>
 					</tr>
                     </tbody>
@@ -204,10 +226,10 @@ pub async fn scoresheet(
 					>"Deductions"</div>
 					<div
 						style="border: 1px solid black; border-width: 1px 1px 1px 0;
-							align-items:center; display:flex; justify-content:end;padding:var(--padding);
-							font-size:var(--text-info);"
+							align-items:center; display:flex; flex-direction:column; justify-content:center;
+							align-items:end; padding:var(--padding); font-size:var(--text-info);"
                         id="deductions"
-					>{format_score(Some(scoresheet.deductions(test)))}</div>
+					>{deduction_report(scoresheet.deduction_report(test))}</div>
 					<div
 						style="border: 1px solid black; border-width: 1px 1px 1px 0;
 							grid-row: 2 / 4; grid-column: 3 / 4;"
@@ -233,7 +255,7 @@ pub async fn scoresheet(
 					>Your score</div>
 					<div id="total-score"
                     >{if !judge.judge.prefs.hide_trend {
-						Some(format_score(scoresheet.score))
+						Some(format_score(scoresheet.score, &test.precision))
 					} else {None}
 					}</div>
 				</div>
@@ -242,33 +264,112 @@ pub async fn scoresheet(
 				<h2>"Judges’ notes "<span>"(These are only visible to you)"</span></h2>
 				<textarea id="private-notes" rows="3">{scoresheet.notes.as_ref()}</textarea>
 			</footer>
-			{start_list_bar::start_list_bar(&show, &competition.starters, judge, &starter.id)}
+			{start_list_bar::start_list_bar(&show, &competition, judge, &starter.id, &ranking)}
 			{warnings_bar::warnings_bar(test, &starter, scoresheet)}
 			<aside id="alerts-and-warnings" style="top:6rem; left:2rem; position:fixed;">
                 {&warnings}
 			</aside>
             <aside id="missing-score-aside" style="position:fixed;pointer-events:none; inset:20%">
             </aside>
+            <aside id="history-aside"></aside>
+            <aside id="starter-notice"></aside>
+            <aside id="amendment-aside">{amendment::amendment_status(scoresheet.amendments.last())}</aside>
 		</main>
 	</main>
 	}.render()))
 }
 
-fn format_score(score: Option<Decimal>) -> String {
+pub fn starter_names<'a>(starter: &'a Starter) -> Lazy<impl Fn(&mut String) + 'a> {
+    rsx_move! {
+        <h1 id="athlete-name">{starter.name()}</h1>
+        <h2 id="horse-name">
+            <span style="color:var(--theme);font-weight:bold">{&starter.competitor.comp_no}</span>
+            <span style="padding-inline:0.5rem">|</span>
+            <span>{starter.horse()}</span>
+        </h2>
+    }
+}
+
+/// Tells the judge the scorer changed the details of the starter on screen
+pub fn starter_notice<'a>(changes: Vec<String>) -> Lazy<impl Fn(&mut String) + 'a> {
+    rsx_move! {
+        <div
+            style="position:fixed; inset:auto 2rem 2rem auto; padding:var(--padding) calc(2 * var(--padding));
+                background:var(--foreground); border:2px solid var(--theme); border-radius:var(--corner-size);
+                font-size:var(--text-info); box-shadow: 0 0.2rem 0.2rem #0003"
+            onclick="this.remove()"
+        >
+            <strong>"The scorer updated this starter"</strong>
+            <ul style="margin:0; padding-inline-start:1rem">
+                @for change in changes.iter() {
+                    <li>{change}</li>
+                }
+            </ul>
+        </div>
+    }
+}
+
+pub fn reset_notice<'a>() -> Lazy<impl Fn(&mut String) + 'a> {
+    rsx_move! {
+        <div
+            style="position:fixed; inset:6rem 2rem auto 2rem; padding:var(--padding) calc(2 * var(--padding));
+                background:var(--error); color:var(--foreground); border-radius:var(--corner-size);
+                font-size:var(--text-info); text-align:center; box-shadow: 0 0.2rem 0.2rem #0003"
+            onclick="this.remove()"
+        >
+            <strong>"The scorer reset the sheet"</strong>
+            " All marks, penalties and signals for this starter have been cleared."
+        </div>
+    }
+}
+
+pub fn unsubscribed_notice<'a>() -> Lazy<impl Fn(&mut String) + 'a> {
+    rsx_move! {
+        <div
+            style="position:fixed; inset:6rem 2rem auto 2rem; padding:var(--padding) calc(2 * var(--padding));
+                background:var(--error); color:var(--foreground); border-radius:var(--corner-size);
+                font-size:var(--text-info); text-align:center; box-shadow: 0 0.2rem 0.2rem #0003"
+            onclick="this.remove()"
+        >
+            <strong>"The server closed this competition"</strong>
+            " Trends and locks will no longer arrive. Open the competition again to follow it."
+        </div>
+    }
+}
+
+pub fn format_score(score: Option<Decimal>, precision: &Precision) -> String {
     match score {
-        Some(s) => format!("{}", s.to_precision(3)),
+        Some(s) => precision.format(s),
         None => String::new(),
     }
 }
 
+pub fn deduction_report<'a>(report: DeductionReport) -> Lazy<impl Fn(&mut String) + 'a> {
+    rsx_move! {
+        <div>{format_score(Some(report.total()), &report.precision)}</div>
+        @if !report.is_empty() {
+            <ul class="deduction-report" style="margin:0; padding:0; list-style:none; font-size:0.6rem; text-align:end">
+                @for penalty in report.penalties.iter() {
+                    <li>{penalty.to_string()}</li>
+                }
+            </ul>
+        }
+    }
+}
+
 pub fn header_trend(
     score: Option<Decimal>,
     rank: Option<u16>,
     provisional: bool,
+    breakdown: Option<TrendBreakdown>,
+    precision: Precision,
 ) -> hypertext::Lazy<impl Fn(&mut String)> {
-    let score = format_score(score);
+    let score = format_score(score, &precision);
     let rank = match provisional {
-        true => "Provisional".to_string(),
+        true => match rank {
+            Some(r) if r > 0 => format!("Provisional rank {r}"),
+            _ => "Provisional".to_string(),
+        },
         false => format!("Rank {:.0}", rank.unwrap_or_default().clone()),
     };
 
@@ -283,6 +384,46 @@ pub fn header_trend(
         <div style=format!("font-size:var(--text-info); {}", if provisional {"color:hsl(0,100%,33%)"} else {""})
             >{rank.to_string()}
         </div>
+        @if let Some(ref breakdown) = breakdown {
+            {trend_breakdown(breakdown)}
+        }
+    }
+}
+
+pub fn trend_breakdown(breakdown: &TrendBreakdown) -> Lazy<impl Fn(&mut String) + '_> {
+    rsx! {
+        <div class="trend-breakdown" style="font-size:0.6rem; white-space:nowrap">
+            <span title="Technical">"T "{format_score(Some(breakdown.technical), &breakdown.precision)}"%"</span>
+            <span style="padding-inline:0.3rem">|</span>
+            <span title="Artistic">"A "{format_score(Some(breakdown.artistic), &breakdown.precision)}"%"</span>
+            @if breakdown.errors_deduction != Decimal::default() {
+                <span style="padding-inline-start:0.3rem">"Err. -"{format_score(Some(breakdown.errors_deduction), &breakdown.precision)}</span>
+            }
+            @if breakdown.technical_deduction != Decimal::default() {
+                <span style="padding-inline-start:0.3rem">"Tech. -"{format_score(Some(breakdown.technical_deduction), &breakdown.precision)}</span>
+            }
+            @if breakdown.artistic_deduction != Decimal::default() {
+                <span style="padding-inline-start:0.3rem">"Art. -"{format_score(Some(breakdown.artistic_deduction), &breakdown.precision)}</span>
+            }
+        </div>
+    }
+}
+
+pub fn difficulty_suggestion(evaluation: &DifficultyEvaluation) -> Lazy<impl Fn(&mut String) + '_> {
+    rsx! {
+        <div style="padding-inline:.1rem; font-size:0.6rem; opacity:0.8">
+            @if let Some((_, mark)) = evaluation.suggestion {
+                <span>"Suggested "{mark.to_string()}</span>
+            }
+            <span style="padding-inline-start:0.3rem">
+                "Shown "{evaluation.shown.len()}"/"{evaluation.declared}
+            </span>
+            @if !evaluation.repeats.is_empty() {
+                <span style="padding-inline-start:0.3rem" title="Repeats do not count">
+                    "Repeated "{evaluation.repeats.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(", ")}
+                </span>
+            }
+        </div>
     }
 }
 
@@ -293,6 +434,8 @@ pub fn scoresheet_rows<'a, 'b>(
     is_freestyle_mode: bool,
 ) -> Lazy<impl Fn(&mut String) + use<'a, 'b>> {
     let movements = test.movements.clone();
+    let evaluation = is_freestyle_mode.then(|| scoresheet.evaluate_difficulty(test));
+    let amendable = scoresheet.amendable();
     let marked_exercises = zip_exercise_and_marks(movements, scoresheet.scores.drain(..).collect());
 
     rsx_move! {
@@ -329,10 +472,15 @@ pub fn scoresheet_rows<'a, 'b>(
                         {get_attempt_buttons(mark)}
                     }
                     </div>
+                    @if let Some(evaluation) = evaluation.as_ref().filter(|e| e.suggestion.is_some_and(|(n, _)| n == x.number)) {
+                        <div class="difficulty-suggestion">
+                            {difficulty_suggestion(evaluation)}
+                        </div>
+                    }
                 }
             </td>
             <td class="exercise-mark input">
-                {get_main_mark_input(marked_exercise, x, scoresheet.locked)}
+                {get_main_mark_input(marked_exercise, x, scoresheet.locked && !amendable.contains(&(x.number as u16)))}
             </td>
             <td
                 class="exercise-coefficient"
@@ -342,21 +490,15 @@ pub fn scoresheet_rows<'a, 'b>(
             } else {"".to_string()}}</td>
             @if is_freestyle_mode && x.category.has_attempts() {
                 <td class="exercise-attempt input">
-                    @if scoresheet.locked {
-                        <input type="text" class="exercise-input" disabled data-input-role="attempt">
-                    } @else {
-                        {attempt_input(x.number, marked_exercise.as_ref().map_or(0, |x|x.attempts.len()))}
-                    }
+                    {attempt_cell(x.number, marked_exercise.as_ref().map_or(0, |x|x.attempts.len()), scoresheet.locked)}
                 </td>
             }
             <td class="exercise-remark input" colspan={if is_freestyle_mode && !x.category.has_attempts() {2}else{1}}>
-                <textarea
-                    class="exercise-input"
-                    data-input-role="remark"
-                    data-index=x.number
-                    oninput="if (this.clientHeight < this.scrollHeight) this.style.minHeight = this.scrollHeight+'px';
-                        window.invoke('input_comment', {value:this.value, index:this.dataset.index});"
-                >@if let Some(x) = marked_exercise.clone() {{x.remark.clone()}}</textarea>
+                {remark_cell(
+                    x.number,
+                    marked_exercise.as_ref().and_then(|x| x.remark.clone()),
+                    scoresheet.locked && !amendable.contains(&(x.number as u16)),
+                )}
             </td>
         </tr>
         }
@@ -439,6 +581,57 @@ pub fn get_main_mark_input<'a>(
     }
 }
 
+/// The contents of a movement's mark cell, showing the judge's own mark
+/// struck through when the scorer has corrected it
+pub fn mark_cell<'a>(
+    marked_exercise: &'a Option<ScoredMark>,
+    x: &'a Exercise,
+    locked: bool,
+    correction: Option<&'a Correction>,
+) -> hypertext::Lazy<impl Fn(&mut String) + 'a> {
+    rsx_move! {
+        {get_main_mark_input(marked_exercise, x, locked)}
+        @if let Some(correction) = correction {
+            <s
+                class="corrected-from"
+                title="Corrected by the scorer"
+                style="opacity:0.6; font-size:var(--text-info)"
+            >{correction.original.map_or("-".to_string(), |x| x.round(1).to_string())}</s>
+        }
+    }
+}
+
+pub fn attempt_cell(number: u8, attempts: usize, locked: bool) -> Lazy<impl Fn(&mut String)> {
+    rsx_move! {
+        @if locked {
+            <input type="text" class="exercise-input" disabled data-input-role="attempt">
+        } @else {
+            {attempt_input(number, attempts)}
+        }
+    }
+}
+
+pub fn remark_cell(number: u8, remark: Option<String>, locked: bool) -> Lazy<impl Fn(&mut String)> {
+    rsx_move! {
+        @if locked {
+            <textarea
+                class="exercise-input"
+                data-input-role="remark"
+                data-index=number
+                disabled
+            >{remark.clone()}</textarea>
+        } @else {
+            <textarea
+                class="exercise-input"
+                data-input-role="remark"
+                data-index=number
+                oninput="if (this.clientHeight < this.scrollHeight) this.style.minHeight = this.scrollHeight+'px';
+                    window.invoke('input_comment', {value:this.value, index:this.dataset.index});"
+            >{remark.clone()}</textarea>
+        }
+    }
+}
+
 pub fn zip_exercise_and_marks(
     mut exercises: Vec<Exercise>,
     mut marks: Vec<ScoredMark>,
@@ -539,9 +732,9 @@ pub fn status_selection<'b>(status: StarterResult) -> Lazy<impl Fn(&mut String)
         </optgroup>
         </select>
         @if let Eliminated(ref value) = status {
-        <select>
-            <option value=value>Option</option>
-        </select>
+            @if !value.is_empty() {
+                <output class="elimination-reason">{value}</output>
+            }
         }
     }
 }
@@ -568,6 +761,70 @@ border-radius: var(--corner-size);border: none;padding: calc(2 * var(--padding))
         <script>{script}</script>
     }
 }
+pub fn missing_remarks_dialog<'a>(movements: Vec<u8>) -> Lazy<impl Fn(&mut String) + 'a> {
+    let selector = movements
+        .iter()
+        .map(|x| format!("tr[data-index='{x}'] textarea[data-input-role='remark']"))
+        .collect::<Vec<_>>()
+        .join(",");
+    let script = Raw(format!(
+        r#"document.querySelector('#missing-movement-dialog')?.showModal();
+    for (let e of document.querySelectorAll("{selector}")) e.placeholder = "Remark required";
+    document.querySelector("{selector}")?.scrollIntoView({{behavior:'smooth',block:'center'}});"#
+    ));
+    let movements = movements.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    rsx_move! {
+        <dialog
+            id="missing-movement-dialog"
+            style="pointer-events:all;background: var(--error);color: var(--foreground);border-radius: var(--corner-size);border-color: color-mix(in srgb, var(--error), black);"
+        >
+            <form method="dialog">
+                <h2>"⚠️ Cannot Submit!"</h2>
+                <p style="font-size:var(--font-info)">"The following movements have a low mark and need a remark:"</p>
+                <p>{movements.join(", ")}</p>
+                <button
+                    style="font-size: var(--text-info);
+background: color-mix(in srgb, var(--error), black);color: var(--foreground);
+border-radius: var(--corner-size);border: none;padding: calc(2 * var(--padding));"
+            >"Ok, I'll comment on them"</button>
+            </form>
+        </dialog>
+        <script>{&script}</script>
+    }
+}
+
+pub fn edit_refused_dialog<'a>(refusal: &EditRefused) -> Lazy<impl Fn(&mut String) + 'a> {
+    let reason = refusal.to_string();
+    let (title, advice) = match refusal {
+        EditRefused::OutboxFull => (
+            "📡 Marks not sent",
+            "Reconnect to the server so the marks already given can reach it.",
+        ),
+        _ => (
+            "🔒 Scoresheet locked",
+            "Request an amendment to correct a confirmed mark.",
+        ),
+    };
+    rsx_move! {
+        <dialog
+            id="edit-refused-dialog"
+            style="pointer-events:all;background: var(--error);color: var(--foreground);border-radius: var(--corner-size);border-color: color-mix(in srgb, var(--error), black);"
+        >
+            <form method="dialog">
+                <h2>{title}</h2>
+                <p style="font-size:var(--font-info)">{&reason}</p>
+                <p style="font-size:var(--font-info)">{advice}</p>
+                <button
+                    style="font-size: var(--text-info);
+background: color-mix(in srgb, var(--error), black);color: var(--foreground);
+border-radius: var(--corner-size);border: none;padding: calc(2 * var(--padding));"
+            >"Ok"</button>
+            </form>
+        </dialog>
+        <script>{Raw("document.querySelector('#edit-refused-dialog')?.showModal();")}</script>
+    }
+}
+
 pub fn get_attempt_buttons<'a>(movement: &'a ScoredMark) -> Lazy<impl Fn(&mut String) + 'a> {
     rsx! {
         @for (x, i) in movement.attempts.iter().zip(0..movement.attempts.len()) {
@@ -615,10 +872,11 @@ pub fn get_timing_section<'a>(
 
 pub fn get_confirm_or_signature<'a>(
     locked: bool,
+    amending: bool,
     signature: Option<Signature>,
 ) -> Lazy<impl Fn(&mut String) + use<'a>> {
     rsx_move! {
-        @if locked {
+        @if locked && !amending {
             <style onload="lockMarks"></style>
             <div style="display:flex">
                 <div style="color:var(--theme);font-weight:bold">"Marks"<br/>"confirmed!"</div>
@@ -626,6 +884,22 @@ pub fn get_confirm_or_signature<'a>(
                     <path stroke="blue" fill="none" d=signature></path>
                 </svg>
             </div>
+            <button
+                type="button"
+                style="font-size:var(--text-info); margin-block-end:0.5rem"
+                tx-command="show_amendment_form"
+            >"Request amendment"</button>
+        } @else if amending {
+            <button
+                style="background:var(--theme); color:white; border-radius:0.25rem;
+                    border:1px solid color-mix(in srgb, var(--theme) 92%, black);
+                    font-size:var(--text-info); padding:var(--padding);
+                    margin-block: 0.5rem 1rem"
+                tx-command="confirm_marks"
+                type="button"
+            >
+            "Confirm Amendments"
+            </button>
         } @else {
             <button
                 style="background:var(--theme); color:white; border-radius:0.25rem;
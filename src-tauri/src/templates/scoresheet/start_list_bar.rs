@@ -5,7 +5,9 @@ use crate::commands::replace_director::PageLocation;
 use crate::templates::icons;
 use crate::{
     domain::{
+        competition::Competition,
         ground_jury_member::{GroundJuryMember, JuryAuthority},
+        ranking::Ranking,
         show::Show,
         starter::Starter,
         SurrealId,
@@ -15,9 +17,10 @@ use crate::{
 
 pub fn start_list_bar<'b, 'a>(
     show: &'b Show,
-    start_list: &'b Vec<Starter>,
+    competition: &'b Competition,
     judge: &'b GroundJuryMember,
     current_starter: &'b SurrealId,
+    ranking: &'b Ranking,
 ) -> Lazy<impl Fn(&mut String) + use<'a, 'b>> {
     let show_id = show.get_id();
     rsx_move! {<aside>
@@ -73,7 +76,7 @@ pub fn start_list_bar<'b, 'a>(
                     </div>
                 </div>
                 <div style="padding:calc(2 * var(--padding)); font-size:var(--text" id="starters-list">
-                    {get_starters_list(start_list, current_starter, judge, None)}
+                    {get_starters_list(competition, current_starter, judge, ranking, None)}
                 </div>
             </div>
         </dialog>
@@ -82,15 +85,16 @@ pub fn start_list_bar<'b, 'a>(
 }
 
 pub fn get_starters_list<'a>(
-    start_list: &'a Vec<Starter>,
+    competition: &'a Competition,
     current_starter: &'a SurrealId,
     judge: &'a GroundJuryMember,
+    ranking: &'a Ranking,
     filter_term: Option<String>,
 ) -> Lazy<impl Fn(&mut String) + 'a> {
     let mut competition_finished = true;
     let mut finished_starters: Vec<&Starter> = vec![];
     let mut upcoming_starters: Vec<&Starter> = vec![];
-    for starter in start_list {
+    for starter in competition.starters.iter() {
         if !starter.status.is_finished() {
             competition_finished = false;
         }
@@ -123,8 +127,20 @@ pub fn get_starters_list<'a>(
     .render();
 
     let separator = !finished_starters.is_empty() && !upcoming_starters.is_empty();
-    let finished = list(finished_starters, true, current_starter);
-    let to_come = list(upcoming_starters, false, current_starter);
+    let finished = list(
+        finished_starters,
+        true,
+        current_starter,
+        ranking,
+        competition,
+    );
+    let to_come = list(
+        upcoming_starters,
+        false,
+        current_starter,
+        ranking,
+        competition,
+    );
     rsx_move! {
         <ul style="margin:0; padding:0">{&finished}</ul>
         @if separator {
@@ -139,6 +155,8 @@ fn list<'b>(
     starters: Vec<&'b Starter>,
     finished: bool,
     current_starter: &'b SurrealId,
+    ranking: &'b Ranking,
+    competition: &'b Competition,
 ) -> Lazy<impl Fn(&mut String) + use<'b>> {
     rsx_move! {
         @for x in starters.iter() {
@@ -153,9 +171,9 @@ fn list<'b>(
                     <div class="starter-select">
                         <div class=format!("done-icon {is_done}")>{x.status.list_abbreviation().to_string()}</div>
                         <div>{format!("{} {}", x.competitor.first_name, x.competitor.last_name)}</div>
-                        <div>{x.score_or_number()}</div>
+                        <div>{x.score_or_number(&x.precision(competition))}</div>
                         <div>{&x.competitor.horse_name} <span class="comp-no">{&x.competitor.comp_no}</span></div>
-                        <div>{x.time_or_rank()}</div>
+                        <div>{x.time_or_rank(ranking.rank(x))}</div>
                     </div>
                 </button>
             </li>
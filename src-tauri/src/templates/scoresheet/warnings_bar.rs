@@ -13,6 +13,8 @@ pub fn warnings_bar<'b>(
     scoresheet: &'b Scoresheet,
 ) -> Lazy<impl Fn(&mut String) + 'b> {
     let status_selection_html = status_selection(starter.status.clone());
+    // the fatal problems keep the scoresheet from opening at all
+    let diagnostics = test.validate();
     rsx_move! {
         <aside>
             <button
@@ -51,16 +53,27 @@ pub fn warnings_bar<'b>(
                         <legend>"Penalties"</legend>
                         <div>
                             <div class="penalty-row" id="penalties-errors">
-                                {errors_row(test.errors_of_course.is_empty(), scoresheet.errors)}
+                                {errors_row(!test.errors_of_course.is_empty(), scoresheet.errors)}
                             </div>
                             <div class="penalty-row" id="penalties-technical">
-                                {technical_row(test.technical_penalties.is_empty(), scoresheet.tech_penalties)}
+                                {technical_row(!test.technical_penalties.is_empty(), scoresheet.tech_penalties)}
                             </div>
                             <div class="penalty-row" id="penalties-artistic">
-                                {artistic_row(test.artistic_penalties.is_empty(), scoresheet.art_penalties)}
+                                {artistic_row(!test.artistic_penalties.is_empty(), scoresheet.art_penalties)}
                             </div>
                         </div>
                     </fieldset>
+
+                    @if !diagnostics.is_empty() {
+                        <fieldset>
+                            <legend>"Test Sheet"</legend>
+                            <ul class="test-diagnostics" style="margin:0; color:var(--error); font-size:var(--text-info)">
+                                @for diagnostic in diagnostics.iter() {
+                                    <li>{diagnostic.to_string()}</li>
+                                }
+                            </ul>
+                        </fieldset>
+                    }
                 </div>
             </dialog>
         </aside>
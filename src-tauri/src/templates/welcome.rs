@@ -75,7 +75,7 @@ pub async fn welcome(
 
 fn show_list<'a>(shows: &'a Shows) -> hypertext::Lazy<impl Fn(&mut String) + use<'a>> {
     rsx_move! {
-        @for (x, _) in shows.0.iter().zip(1..) {
+        @for (x, problems) in shows.0.iter().map(|x| (x, x.diagnostics().len())) {
             <li
                 tx-goto="competition_list"
                 tx-id=x.get_id()
@@ -83,6 +83,11 @@ fn show_list<'a>(shows: &'a Shows) -> hypertext::Lazy<impl Fn(&mut String) + use
             >
                 <div style="color:white">{ &x.name }</div>
                 <div style="color:silver">"@ "{ &x.venue }</div>
+                @if problems > 0 {
+                    <div class="test-diagnostics" style="color:var(--error); font-size:var(--text-info)">
+                        {format!("{problems} test sheet problem(s)")}
+                    </div>
+                }
             </li>
         }
     }